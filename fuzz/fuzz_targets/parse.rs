@@ -0,0 +1,29 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate literalext;
+
+use literalext::parse;
+
+// None of the parsers should panic, whatever text they are given. Arbitrary
+// bytes are converted lossily, so that invalid UTF-8 still reaches them.
+fuzz_target!(|data: &[u8]| {
+    let s = String::from_utf8_lossy(data);
+    let _ = parse::value(&s);
+    let _ = parse::kind(&s);
+    let _ = parse::int(&s);
+    let _ = parse::int_lenient(&s);
+    let _ = parse::signed_int(&s);
+    let _ = parse::float(&s);
+    let _ = parse::try_string(&s);
+    let _ = parse::string_len(&s);
+    let _ = parse::string_lit(&s);
+    let _ = parse::string_cow(&s);
+    let _ = parse::try_char(&s);
+    let _ = parse::bytes(&s);
+    let _ = parse::bytes_iter(&s).map(|i| i.count());
+    let _ = parse::cstr(&s);
+    let _ = parse::byte(&s);
+    let _ = parse::doc_from_attr(&s);
+    let _ = literalext::escape::decode_escape(&s);
+});
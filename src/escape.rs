@@ -0,0 +1,171 @@
+//! Functions for decoding the escape sequences used in Rust's string and char
+//! literals, for use by macros which accept bespoke string-like literals, and
+//! for encoding values as literals, for use by code generators.
+
+use alloc::string::String;
+
+use LiteralError;
+use internal;
+
+/// Decode the escape sequence at the start of the text, such as `\n`, `\x41`
+/// or `\u{1F600}`, returning the decoded character and the remaining text.
+/// Returns `NotThisKind` if the text does not start with a `\`. Line
+/// continuations are not escapes, and are rejected as `InvalidEscape`.
+pub fn decode_escape(s: &str) -> Result<(char, &str), LiteralError> {
+    internal::char_escape(s, 0)
+}
+
+/// A piece of the body of a string literal, as returned by `next`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EscapeToken<'a> {
+    /// An escape sequence such as `\n`, or a `\r\n` line ending, which
+    /// decodes to the char.
+    Char(char),
+    /// A `\` line continuation and the whitespace after it, none of which is
+    /// part of the value.
+    Continuation,
+    /// A run of text without escapes, which is part of the value unchanged.
+    Literal(&'a str),
+}
+
+/// Decode the piece of a string literal's body at the start of the text,
+/// returning it along with the number of bytes it was written with. The text
+/// should start after the opening `"`, and be advanced by the returned length
+/// for each piece, until this returns `None` at the closing `"`. Also returns
+/// `None` for empty text, a malformed escape sequence, or a bare carriage
+/// return, which are not valid in strings.
+pub fn next(s: &str) -> Option<(EscapeToken<'_>, usize)> {
+    match s.as_bytes().first()? {
+        b'"' => None,
+        b'\\' if s[1..].starts_with(['\r', '\n']) => {
+            Some((EscapeToken::Continuation, 2 + internal::continuation_len(&s[2..])))
+        }
+        b'\\' => {
+            let (ch, rest) = internal::char_escape(s, 0).ok()?;
+            Some((EscapeToken::Char(ch), s.len() - rest.len()))
+        }
+        b'\r' if s[1..].starts_with('\n') => Some((EscapeToken::Char('\n'), 2)),
+        b'\r' => None,
+        _ => {
+            let len = s.find(['"', '\\', '\r']).unwrap_or(s.len());
+            Some((EscapeToken::Literal(&s[..len]), len))
+        }
+    }
+}
+
+/// Encode the string as a string literal, such that `parse::string` returns it
+/// unchanged. Quotes, backslashes and non-printable characters are escaped,
+/// such as `"a\"b\n"` for `a"b` followed by a newline.
+pub fn encode_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            // NOTE: `escape_debug` escapes `'`, which is unnecessary in strings.
+            '\'' => out.push(ch),
+            _ => out.extend(ch.escape_debug()),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Encode the bytes as a byte string literal, such that `parse::bytes` returns
+/// them unchanged. Bytes which are not printable ASCII are written as hex
+/// escapes, such as `b"a\xff"`.
+pub fn encode_byte_str(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 3);
+    out.push_str("b\"");
+    for &b in bytes {
+        match b {
+            b'\'' => out.push('\''),
+            _ => out.extend(b.escape_ascii().map(char::from)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_escape() {
+    assert_eq!(decode_escape("\\nrest"), Ok(('\n', "rest")));
+    assert_eq!(decode_escape("\\\\"), Ok(('\\', "")));
+    assert_eq!(decode_escape("\\x41b"), Ok(('A', "b")));
+    assert_eq!(decode_escape("\\u{1F600}"), Ok(('\u{1F600}', "")));
+    assert_eq!(decode_escape("n"), Err(LiteralError::NotThisKind));
+    assert_eq!(decode_escape("\\"), Err(LiteralError::UnterminatedLiteral { offset: 1 }));
+    assert_eq!(decode_escape("\\q"), Err(LiteralError::InvalidEscape { offset: 0 }));
+    assert_eq!(decode_escape("\\x80"), Err(LiteralError::InvalidEscape { offset: 0 }));
+    assert_eq!(decode_escape("\\u{D800}"), Err(LiteralError::InvalidCodePoint { offset: 0 }));
+    assert_eq!(decode_escape("\\\n"), Err(LiteralError::InvalidEscape { offset: 0 }));
+}
+
+#[cfg(test)]
+#[test]
+fn test_next() {
+    use alloc::vec::Vec;
+
+    fn pieces(mut s: &str) -> (Vec<(EscapeToken<'_>, usize)>, &str) {
+        let mut out = Vec::new();
+        while let Some((token, len)) = next(s) {
+            out.push((token, len));
+            s = &s[len..];
+        }
+        (out, s)
+    }
+
+    assert_eq!(pieces(r#"ab\n\u{1F600}c" rest"#), (vec![
+        (EscapeToken::Literal("ab"), 2),
+        (EscapeToken::Char('\n'), 2),
+        (EscapeToken::Char('\u{1F600}'), 9),
+        (EscapeToken::Literal("c"), 1),
+    ], "\" rest"));
+    assert_eq!(pieces("a\\\n  \tb\r\nc\"").0, vec![
+        (EscapeToken::Literal("a"), 1),
+        (EscapeToken::Continuation, 5),
+        (EscapeToken::Literal("b"), 1),
+        (EscapeToken::Char('\n'), 2),
+        (EscapeToken::Literal("c"), 1),
+    ]);
+    assert_eq!(pieces("\\\r\n x").0, vec![
+        (EscapeToken::Continuation, 4),
+        (EscapeToken::Literal("x"), 1),
+    ]);
+    assert_eq!(next("é\\x41"), Some((EscapeToken::Literal("é"), 2)));
+    assert_eq!(next("\\x41\""), Some((EscapeToken::Char('A'), 4)));
+
+    assert_eq!(next(""), None);
+    assert_eq!(next("\""), None);
+    assert_eq!(next("\\"), None);
+    assert_eq!(next("\\q"), None);
+    assert_eq!(next("\\x80"), None);
+    assert_eq!(next("\\u{D800}"), None);
+    assert_eq!(next("\\u{41"), None);
+    assert_eq!(next("\ra"), None);
+    assert_eq!(next("\\é"), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_encode() {
+    use alloc::vec::Vec;
+
+    assert_eq!(encode_str("a\"b'\\\n"), r#""a\"b'\\\n""#);
+    assert_eq!(encode_str("\u{0}\u{7f}\u{e9}"), "\"\\0\\u{7f}\u{e9}\"");
+    assert_eq!(encode_byte_str(b"a\"'\0\xff"), r#"b"a\"'\x00\xff""#);
+
+    // Every char and byte survives a round trip, alone and among others.
+    let chars = (0..0x3000).chain(0xFFF0..0x10100).chain(0x10FFF0..0x110000);
+    for ch in chars.filter_map(char::from_u32) {
+        let s: String = ['a', ch, '"', ch].iter().collect();
+        assert_eq!(internal::str_lit(&encode_str(&s)), Ok(s));
+    }
+    let all: Vec<u8> = (0..=255).collect();
+    assert_eq!(internal::byte_str_lit(&encode_byte_str(&all)), Some(all.clone()));
+    for &b in &all {
+        assert_eq!(internal::byte_str_lit(&encode_byte_str(&[b])), Some(vec![b]));
+    }
+    assert_eq!(internal::str_lit(&encode_str("")), Ok(String::new()));
+    assert_eq!(internal::byte_str_lit(&encode_byte_str(b"")), Some(Vec::new()));
+}
@@ -1,8 +1,53 @@
-use {RawInt, IntLit, FloatLit};
+use {RawInt, IntBase, IntLit, FloatLit, FloatRepr, LongDigits, StrLit, CharLit, ByteLit,
+     EscapeKind, ByteStrIter, StrIter, LiteralError, LiteralDiagnostic, LiteralKind, LitValue,
+     ParsedLit};
 
-use std::char;
-use std::ops::{Index, RangeFrom};
-use std::ascii::AsciiExt;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::char;
+use core::fmt;
+use core::ops::{Index, Range, RangeFrom};
+
+#[cfg(feature = "serde")]
+use Radix;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+#[cfg(feature = "serde")]
+use core::convert::TryFrom;
+
+#[cfg(feature = "intern")]
+use std::cell::RefCell;
+#[cfg(feature = "intern")]
+use std::collections::HashSet;
+#[cfg(feature = "intern")]
+use std::sync::Arc;
+
+/// Lex the text as a single `proc_macro2` literal token, or return `None` if it
+/// is not one.
+#[cfg(feature = "proc-macro2")]
+pub(crate) fn lex_literal(text: &str) -> Option<proc_macro2::Literal> {
+    let mut trees = text.parse::<proc_macro2::TokenStream>().ok()?.into_iter();
+    match (trees.next(), trees.next()) {
+        (Some(proc_macro2::TokenTree { kind: proc_macro2::TokenNode::Literal(lit), .. }), None) => {
+            Some(lit)
+        }
+        _ => None,
+    }
+}
+
+/// Get the text of a literal token, without any surrounding ASCII whitespace,
+/// which some backends include when rendering it.
+#[cfg(any(feature = "dummy", feature = "proc-macro", feature = "proc-macro2"))]
+pub(crate) fn token_text<T: fmt::Display + ?Sized>(token: &T) -> String {
+    let text = token.to_string();
+    let trimmed = text.trim_matches(|c: char| c.is_ascii_whitespace());
+    if trimmed.len() == text.len() {
+        text
+    } else {
+        trimmed.to_string()
+    }
+}
 
 /// Filter the input string, removing all bytes which match the given input
 /// byte in place, without allocation.
@@ -11,11 +56,21 @@ use std::ascii::AsciiExt;
 ///
 /// Panics if the filter byte is not a valid ASCII character.
 fn string_filter(string: String, remove: u8) -> String {
+    string_filter_many(string, &[remove])
+}
+
+/// Filter the input string, removing all bytes which match any of the given
+/// input bytes in place, without allocation.
+///
+/// # Panics
+///
+/// Panics if any of the filter bytes is not a valid ASCII character.
+fn string_filter_many(string: String, remove: &[u8]) -> String {
     assert!(remove.is_ascii());
     let mut bytes = string.into_bytes();
     let mut write = 0;
     for read in 0..bytes.len() {
-        if bytes[read] == remove {
+        if remove.contains(&bytes[read]) {
             continue; // Don't increase write
         } else if write != read {
             let x = bytes[read];
@@ -39,6 +94,10 @@ fn test_string_filter() {
                "12837912837192837129387129");
     assert_eq!(string_filter("_12837912837192837129387129".to_string(), b'_'),
                "12837912837192837129387129");
+
+    assert_eq!(string_filter_many("1_000 000_é".to_string(), b"_ "), "1000000é");
+    assert_eq!(string_filter_many(" _ _ ".to_string(), b"_ "), "");
+    assert_eq!(string_filter_many("1_0".to_string(), b""), "1_0");
 }
 
 /// Get the byte at offset idx, or a default of `b'\0'` if we're looking past
@@ -56,272 +115,484 @@ fn next_chr(s: &str) -> char {
     s.chars().next().unwrap_or('\0')
 }
 
-fn raw_str(s: &str) -> &str {
-    // NOTE: This makes very very strong assumptions about the formatting of raw
-    // string literals. If you pass a malformed literal into this function it is
-    // very likely to panic or do the wrong thing.
-    let begin = s.find('"').expect("Raw string must begin with \" char");
-    let end = s.rfind('"').expect("Raw string must end with \" char");
-    &s[begin + 1..end]
+/// Get the contents of a raw string literal, which may be prefixed by `b` or
+/// `c`. The closing `"` must be followed by the same number of `#` characters
+/// as the opening one is preceded by.
+fn raw_str(input: &str) -> Result<&str, LiteralError> {
+    let mut s = input;
+    if byte(s, 0) == b'b' || byte(s, 0) == b'c' {
+        s = &s[1..];
+    }
+    if byte(s, 0) != b'r' {
+        return Err(LiteralError::NotThisKind);
+    }
+    s = &s[1..];
+
+    let hashes = s.bytes().take_while(|&b| b == b'#').count();
+    s = &s[hashes..];
+    if byte(s, 0) != b'"' {
+        // NOTE: This could be a raw identifier, such as `r#foo`.
+        return Err(LiteralError::NotThisKind);
+    }
+    let body = &s[1..];
+
+    for (end, _) in body.match_indices('"') {
+        let rest = &body[end + 1..];
+        if rest.len() >= hashes && rest.bytes().take(hashes).all(|b| b == b'#') {
+            let rest = &rest[hashes..];
+            if !rest.is_empty() {
+                return Err(LiteralError::InvalidSuffix { offset: input.len() - rest.len() });
+            }
+            let body = &body[..end];
+            // NOTE: Raw strings have no escapes, but still may not contain a
+            // bare CR.
+            let bare_cr = body.match_indices('\r').find(|&(i, _)| byte(body, i + 1) != b'\n');
+            if let Some((i, _)) = bare_cr {
+                let offset = input.len() - s.len() + 1 + i;
+                return Err(LiteralError::BareCarriageReturn { offset });
+            }
+            return Ok(body);
+        }
+    }
+    Err(LiteralError::UnterminatedLiteral { offset: input.len() })
+}
+
+/// Get the value of an ASCII digit in any radix up to 36, where the letters
+/// in either case are the digits from 10 upwards.
+fn digit_val(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'z' => Some(10 + (b - b'a')),
+        b'A'..=b'Z' => Some(10 + (b - b'A')),
+        _ => None,
+    }
+}
+
+/// Get the value of a hex digit, in either case.
+fn hex_val(b: u8) -> Option<u8> {
+    digit_val(b).filter(|&d| d < 0x10)
 }
 
-fn backslash_x<S>(s: &S) -> (&S, u8)
+#[cfg(test)]
+#[test]
+fn test_hex_val() {
+    for b in 0..=255u8 {
+        let expected = (b as char).to_digit(16).map(|d| d as u8);
+        assert_eq!(hex_val(b), expected, "{:?}", b as char);
+        assert_eq!(digit_val(b), (b as char).to_digit(36).map(|d| d as u8), "{:?}", b as char);
+    }
+}
+
+/// Parse the two hex digits following a `\x` escape. Returns `None` if the
+/// digits are missing or are not valid hex.
+fn backslash_x<S>(s: &S) -> Option<(&S, u8)>
     where S: Index<RangeFrom<usize>, Output=S> + AsRef<[u8]> + ?Sized
 {
-    let mut ch = 0;
-    let b0 = byte(s, 0);
-    let b1 = byte(s, 1);
-    ch += 0x10 * match b0 {
-        b'0'...b'9' => b0 - b'0',
-        b'a'...b'f' => 10 + (b0 - b'a'),
-        b'A'...b'F' => 10 + (b0 - b'A'),
-        _ => panic!("unexpected non-hex character after \\x"),
-    };
-    ch += 0x1 * match b1 {
-        b'0'...b'9' => b1 - b'0',
-        b'a'...b'f' => 10 + (b1 - b'a'),
-        b'A'...b'F' => 10 + (b1 - b'A'),
-        _ => panic!("unexpected non-hex character after \\x"),
-    };
-    (&s[2..], ch)
+    let hi = hex_val(byte(s, 0))?;
+    let lo = hex_val(byte(s, 1))?;
+    Some((&s[2..], hi * 0x10 + lo))
 }
 
-fn backslash_u(mut s: &str) -> (&str, char) {
+/// Parse the `{...}` following a `\u` escape. Returns the raw code point, which
+/// the caller is responsible for validating, or `None` if the escape is
+/// malformed.
+fn backslash_u(mut s: &str) -> Option<(&str, u32)> {
     if byte(s, 0) != b'{' {
-        panic!("expected {{ after \\u");
+        return None;
     }
     s = &s[1..];
 
     let mut ch = 0;
-    for _ in 0..6 {
+    let mut digits = 0;
+    loop {
         let b = byte(s, 0);
-        match b {
-            b'0'...b'9' => {
-                ch *= 0x10;
-                ch += (b - b'0') as u32;
-                s = &s[1..];
-            }
-            b'a'...b'f' => {
-                ch *= 0x10;
-                ch += (10 + b - b'a') as u32;
-                s = &s[1..];
-            }
-            b'A'...b'F' => {
-                ch *= 0x10;
-                ch += (10 + b - b'A') as u32;
+        let digit = match (b, hex_val(b)) {
+            (_, Some(digit)) => digit,
+            // NOTE: Like rustc, `_` may separate the digits, but not precede
+            // them, and doesn't count towards the limit of 6 digits.
+            (b'_', _) if digits > 0 => {
                 s = &s[1..];
+                continue;
             }
-            b'}' => break,
-            _ => panic!("unexpected non-hex character after \\u"),
+            (b'}', _) if digits > 0 => break,
+            _ => return None,
+        };
+        // NOTE: Leading zeros count, so `\u{0000041}` is too long.
+        if digits == 6 {
+            return None;
         }
+        ch = ch * 0x10 + digit as u32;
+        digits += 1;
+        s = &s[1..];
     }
-    assert!(byte(s, 0) == b'}');
-    s = &s[1..];
+    Some((&s[1..], ch))
+}
 
-    if let Some(ch) = char::from_u32(ch) {
-        (s, ch)
-    } else {
-        panic!("character code {:x} is not a valid unicode character", ch);
-    }
+#[cfg(test)]
+#[test]
+fn test_backslash_u() {
+    assert_eq!(backslash_u("{41}rest"), Some(("rest", 0x41)));
+    assert_eq!(backslash_u("{10FFFF}"), Some(("", 0x10FFFF)));
+    // NOTE: Code points are validated by the caller.
+    assert_eq!(backslash_u("{D800}"), Some(("", 0xD800)));
+    assert_eq!(backslash_u("{110000}"), Some(("", 0x110000)));
+    assert_eq!(backslash_u("41}"), None);
+    assert_eq!(backslash_u("{4g}"), None);
+    assert_eq!(backslash_u("{41"), None);
+    assert_eq!(backslash_u("{1_F4_15}"), Some(("", 0x1F415)));
+    assert_eq!(backslash_u("{4__1_}"), Some(("", 0x41)));
+    assert_eq!(backslash_u("{10_FF_FF}"), Some(("", 0x10FFFF)));
+    assert_eq!(backslash_u("{_41}"), None);
+    assert_eq!(backslash_u("{}"), None);
+    assert_eq!(backslash_u("{1234567}"), None);
+    assert_eq!(backslash_u("{0000041}"), None);
+    assert_eq!(backslash_u("{000041}"), Some(("", 0x41)));
+    assert_eq!(backslash_u("{"), None);
+    assert_eq!(backslash_u("{41_"), None);
 }
 
-pub(crate) fn str_lit(mut s: &str) -> Option<String> {
-    match byte(s, 0) {
-        b'"' => {
-            s = &s[1..]
+/// Decode the escape sequence at the start of `s`, which begins with a `\`,
+/// returning the character and the remaining input. Line continuations are
+/// not handled, as they are only valid in strings. The offsets of any errors
+/// are relative to `s`, plus `base`.
+pub(crate) fn char_escape(s: &str, base: usize) -> Result<(char, &str), LiteralError> {
+    if byte(s, 0) != b'\\' {
+        return Err(LiteralError::NotThisKind);
+    }
+    let b = byte(s, 1);
+    if s.len() < 2 {
+        return Err(LiteralError::UnterminatedLiteral { offset: base + s.len() });
+    }
+    if !b.is_ascii() {
+        return Err(LiteralError::InvalidEscape { offset: base });
+    }
+    let rest = &s[2..];
+    let ch = match b {
+        b'x' => {
+            return match backslash_x(rest) {
+                Some((rest, byte)) if byte < 0x80 => Ok((byte as char, rest)),
+                _ => Err(LiteralError::InvalidEscape { offset: base }),
+            };
         }
-        b'r' => {
-            return Some(raw_str(s).to_string());
+        b'u' => {
+            let (rest, code) = match backslash_u(rest) {
+                Some(x) => x,
+                None => return Err(LiteralError::InvalidEscape { offset: base }),
+            };
+            return match char::from_u32(code) {
+                Some(chr) => Ok((chr, rest)),
+                None => Err(LiteralError::InvalidCodePoint { offset: base }),
+            };
         }
-        _ => return None,
-    }
+        b'n' => '\n',
+        b'r' => '\r',
+        b't' => '\t',
+        b'\\' => '\\',
+        b'0' => '\0',
+        b'\'' => '\'',
+        b'"' => '"',
+        _ => return Err(LiteralError::InvalidEscape { offset: base }),
+    };
+    Ok((ch, rest))
+}
 
+pub(crate) fn str_lit(input: &str) -> Result<String, LiteralError> {
+    if byte(input, 0) == b'r' {
+        return raw_str(input).map(|s| s.to_string());
+    }
     let mut out = String::new();
-    'outer: loop {
-        let ch = match byte(s, 0) {
-            b'"' => break,
-            b'\\' => {
-                let b = byte(s, 1);
-                s = &s[2..];
-                match b {
-                    b'x' => {
-                        let (rest, byte) = backslash_x(s);
-                        s = rest;
-                        assert!(byte <= 0x80, "Invalid \\x byte in string literal");
-                        char::from_u32(byte as u32).unwrap()
-                    }
-                    b'u' => {
-                        let (rest, chr) = backslash_u(&s);
-                        s = rest;
-                        chr
-                    }
-                    b'n' => '\n',
-                    b'r' => '\r',
-                    b't' => '\t',
-                    b'\\' => '\\',
-                    b'0' => '\0',
-                    b'\'' => '\'',
-                    b'"' => '"',
-                    b'\r' | b'\n' => {
-                        loop {
-                            let ch = next_chr(s);
-                            if ch.is_whitespace() {
-                                s = &s[ch.len_utf8()..];
-                            } else {
-                                continue 'outer;
-                            }
-                        }
-                    }
-                    b => {
-                        panic!("unexpected byte {:?} after \\ character in byte literal", b)
-                    }
-                }
-            }
-            b'\r' => {
-                assert_eq!(byte(s, 1), b'\n', "Bare CR not allowed in string");
-                s = &s[2..];
-                '\n'
-            }
-            _ => {
-                let ch = next_chr(s);
-                s = &s[ch.len_utf8()..];
-                ch
-            }
-        };
-        out.push(ch);
+    str_chars(input, |ch| out.push(ch))?;
+    Ok(out)
+}
+
+/// Like `str_lit`, but drops a leading byte order mark from the value.
+pub(crate) fn str_lit_trimmed_bom(input: &str) -> Result<String, LiteralError> {
+    let mut out = str_lit(input)?;
+    if out.starts_with('\u{FEFF}') {
+        out.replace_range(..'\u{FEFF}'.len_utf8(), "");
     }
+    Ok(out)
+}
 
-    assert_eq!(s, "\"");
-    return Some(out);
+#[cfg(feature = "intern")]
+thread_local! {
+    static INTERNED: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
 }
 
-pub(crate) fn byte_str_lit(mut s: &str) -> Option<Vec<u8>> {
-    match (byte(s, 0), byte(s, 1)) {
-        (b'b', b'"') => {
-            s = &s[2..];
-        }
-        (b'b', b'r') => {
-            return Some(raw_str(s).as_bytes().to_vec());
+/// Get this thread's shared copy of the string, interning it if there is none.
+#[cfg(feature = "intern")]
+fn intern(s: &str) -> Arc<str> {
+    INTERNED.with(|set| {
+        let mut set = set.borrow_mut();
+        if let Some(shared) = set.get(s) {
+            return shared.clone();
         }
-        _ => return None,
-    }
-    // We're going to want to have slices which don't respect codepoint boundaries.
-    let mut s = s.as_bytes();
+        let shared: Arc<str> = Arc::from(s);
+        set.insert(shared.clone());
+        shared
+    })
+}
 
-    let mut out = Vec::new();
-    'outer: loop {
-        let byte = match byte(s, 0) {
-            b'"' => break,
-            b'\\' => {
-                let b = byte(s, 1);
-                s = &s[2..];
-                match b {
-                    b'x' => {
-                        let (rest, b) = backslash_x(s);
-                        s = rest;
-                        b
-                    }
-                    b'n' => b'\n',
-                    b'r' => b'\r',
-                    b't' => b'\t',
-                    b'\\' => b'\\',
-                    b'0' => b'\0',
-                    b'\'' => b'\'',
-                    b'"' => b'"',
-                    b'\r' | b'\n' => {
-                        loop {
-                            let byte = byte(s, 0);
-                            let ch = char::from_u32(byte as u32).unwrap();
-                            if ch.is_whitespace() {
-                                s = &s[1..];
-                            } else {
-                                continue 'outer;
-                            }
-                        }
-                    }
-                    b => {
-                        panic!("unexpected byte {:?} after \\ character in byte literal", b)
-                    }
-                }
-            }
-            b'\r' => {
-                assert_eq!(byte(s, 1), b'\n', "Bare CR not allowed in string");
-                s = &s[2..];
-                b'\n'
-            }
-            b => {
-                s = &s[1..];
-                b
-            }
-        };
-        out.push(byte);
+#[cfg(feature = "intern")]
+pub(crate) fn clear_interned() {
+    INTERNED.with(|set| set.borrow_mut().clear());
+}
+
+/// Like `str_lit`, but returns the interned copy of the value. Strings without
+/// escapes are looked up without allocating.
+#[cfg(feature = "intern")]
+pub(crate) fn interned_str(input: &str) -> Option<Arc<str>> {
+    str_lit_cow(input).ok().map(|s| intern(&s))
+}
+
+/// Like `str_lit`, but only counts the `char`s in the string's value, without
+/// allocating.
+pub(crate) fn str_len(input: &str) -> Result<usize, LiteralError> {
+    if byte(input, 0) == b'r' {
+        return raw_str(input).map(|s| s.chars().count());
     }
+    let mut len = 0;
+    str_chars(input, |_| len += 1)?;
+    Ok(len)
+}
 
-    assert_eq!(s, b"\"");
-    return Some(out);
+/// Get the length of the whitespace which follows a `\` line continuation in
+/// a string, byte string, or C string, all of which is skipped. Like rustc,
+/// only ASCII whitespace is skipped, so a unicode space such as `\u{3000}`
+/// after a continuation is kept in the value.
+pub(crate) fn continuation_len<S: AsRef<[u8]> + ?Sized>(s: &S) -> usize {
+    s.as_ref().iter().take_while(|&&b| matches!(b, b' ' | b'\t' | b'\n' | b'\r')).count()
 }
 
-pub(crate) fn char_lit(mut s: &str) -> Option<char> {
-    if byte(s, 0) != b'\'' {
-        return None;
+/// Decode a non-raw string literal, passing each `char` of its value to `f`.
+fn str_chars<F: FnMut(char)>(input: &str, mut f: F) -> Result<(), LiteralError> {
+    let mut s = input;
+    let offset = |s: &str| input.len() - s.len();
+
+    if byte(s, 0) != b'"' {
+        return Err(LiteralError::NotThisKind);
     }
     s = &s[1..];
 
-    let ch = match byte(s, 0) {
-        b'\\' => {
-            let b = byte(s, 1);
-            s = &s[2..];
-            match b {
-                b'x' => {
-                    let (rest, byte) = backslash_x(s);
-                    s = rest;
-                    assert!(byte <= 0x80, "Invalid \\x byte in string literal");
-                    char::from_u32(byte as u32).unwrap()
-                }
-                b'u' => {
-                    let (rest, chr) = backslash_u(s);
-                    s = rest;
-                    chr
-                }
-                b'n' => '\n',
-                b'r' => '\r',
-                b't' => '\t',
-                b'\\' => '\\',
-                b'0' => '\0',
-                b'\'' => '\'',
-                b'"' => '"',
-                b => {
-                    panic!("unexpected byte {:?} after \\ character in byte literal", b)
-                }
+    loop {
+        match byte(s, 0) {
+            _ if s.is_empty() => {
+                return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
             }
+            b'"' => break,
+            _ => {}
+        }
+        let (ch, rest) = str_step(s, offset(s))?;
+        s = rest;
+        if let Some(ch) = ch {
+            f(ch);
+        }
+    }
+
+    if s != "\"" {
+        return Err(LiteralError::InvalidSuffix { offset: offset(s) + 1 });
+    }
+    Ok(())
+}
+
+/// Decode the next char of the body of a non-raw string, which must not be
+/// empty, returning it along with the remaining input. The char is `None` if
+/// the input began with a line continuation. The offsets of any errors are
+/// relative to `s`, plus `base`.
+fn str_step(s: &str, base: usize) -> Result<(Option<char>, &str), LiteralError> {
+    match byte(s, 0) {
+        b'\\' if byte(s, 1) == b'\r' || byte(s, 1) == b'\n' => {
+            let s = &s[2..];
+            Ok((None, &s[continuation_len(s)..]))
         }
+        b'\\' => char_escape(s, base).map(|(ch, rest)| (Some(ch), rest)),
+        b'\r' if byte(s, 1) == b'\n' => Ok((Some('\n'), &s[2..])),
+        b'\r' => Err(LiteralError::BareCarriageReturn { offset: base }),
         _ => {
             let ch = next_chr(s);
-            s = &s[ch.len_utf8()..];
-            ch
+            Ok((Some(ch), &s[ch.len_utf8()..]))
         }
+    }
+}
+
+/// Like `str_lit`, but returns an iterator which decodes the chars of the
+/// literal as they are requested. The literal is validated up front, without
+/// allocating.
+pub(crate) fn str_iter(text: Cow<'_, str>) -> Option<StrIter<'_>> {
+    let (pos, end, raw) = if byte(&*text, 0) == b'r' {
+        let body = raw_str(&text).ok()?;
+        let pos = 2 + text[1..].bytes().take_while(|&b| b == b'#').count();
+        (pos, pos + body.len(), true)
+    } else {
+        str_chars(&text, |_| {}).ok()?;
+        (1, text.len() - 1, false)
     };
-    assert_eq!(s, "\'", "Expected end of char literal");
-    Some(ch)
+    Some(StrIter { text, pos, end, raw })
 }
 
-pub(crate) fn byte_lit(s: &str) -> Option<u8> {
-    if byte(s, 0) != b'b' || byte(s, 1) != b'\'' {
-        return None;
+/// Decode the next char of a `StrIter`.
+pub(crate) fn str_iter_next(iter: &mut StrIter) -> Option<char> {
+    while iter.pos < iter.end {
+        let s = &iter.text[iter.pos..iter.end];
+        if iter.raw {
+            let ch = next_chr(s);
+            iter.pos += ch.len_utf8();
+            return Some(ch);
+        }
+        let (ch, rest) = str_step(s, iter.pos).ok()?;
+        iter.pos = iter.end - rest.len();
+        if ch.is_some() {
+            return ch;
+        }
+    }
+    None
+}
+
+/// Like `str_lit`, but also records whether the string was written as a raw
+/// string, and how many `#` characters delimit it. Unlike `str_lit`, an
+/// identifier suffix such as the `bar` of `"foo"bar` is accepted, as rustc
+/// lexes it as part of the token, and is recorded too.
+pub(crate) fn string_lit(input: &str) -> Result<StrLit, LiteralError> {
+    let (val, suffix) = match str_lit(input) {
+        Ok(val) => (val, ""),
+        Err(LiteralError::InvalidSuffix { offset }) if is_ident(&input[offset..]) => {
+            (str_lit(&input[..offset])?, &input[offset..])
+        }
+        Err(err) => return Err(err),
+    };
+    let raw = byte(input, 0) == b'r';
+    let hash_count = if raw {
+        input[1..].bytes().take_while(|&b| b == b'#').count()
+    } else {
+        0
+    };
+    Ok(StrLit {
+        val,
+        raw,
+        hash_count,
+        suffix: suffix.to_string(),
+    })
+}
+
+/// Count the `#`s delimiting a raw string, raw byte string or raw C string
+/// literal, such as 2 for `br##"a"##`. Returns `None` for any other text.
+pub(crate) fn raw_hash_count(input: &str) -> Option<usize> {
+    raw_str(input).ok()?;
+    let (prefix, _) = str_prefix(input)?;
+    Some(input[prefix.len()..].bytes().take_while(|&b| b == b'#').count())
+}
+
+/// Like `str_lit`, but borrows from the input when the literal's value is a
+/// contiguous slice of it, which is the case for raw strings and for strings
+/// without escapes.
+pub(crate) fn str_lit_cow(input: &str) -> Result<Cow<'_, str>, LiteralError> {
+    match byte(input, 0) {
+        b'"' => {}
+        b'r' => return raw_str(input).map(Cow::Borrowed),
+        _ => return Err(LiteralError::NotThisKind),
+    }
+
+    let body = &input[1..];
+    if let Some(end) = body.find(['"', '\\', '\r']) {
+        if &body[end..] == "\"" {
+            return Ok(Cow::Borrowed(&body[..end]));
+        }
+    }
+    str_lit(input).map(Cow::Owned)
+}
+
+pub(crate) fn byte_str_lit(s: &str) -> Option<Vec<u8>> {
+    try_byte_str_lit(s).ok()
+}
+
+/// Like `byte_str_lit`, but describes why the literal is malformed.
+pub(crate) fn try_byte_str_lit(s: &str) -> Result<Vec<u8>, LiteralError> {
+    if byte(s, 0) == b'b' && byte(s, 1) == b'r' {
+        return raw_str(s).map(|s| s.as_bytes().to_vec());
+    }
+    let mut out = Vec::new();
+    byte_str_each(s, |b| out.push(b))?;
+    Ok(out)
+}
+
+/// Like `byte_str_lit`, but returns an iterator which decodes the bytes of the
+/// literal as they are requested. The literal is validated up front, without
+/// allocating.
+pub(crate) fn byte_str_iter(text: Cow<'_, str>) -> Option<ByteStrIter<'_>> {
+    let (pos, end, raw) = if byte(&*text, 0) == b'b' && byte(&*text, 1) == b'r' {
+        let body = raw_str(&text).ok()?;
+        let pos = 3 + text[2..].bytes().take_while(|&b| b == b'#').count();
+        (pos, pos + body.len(), true)
+    } else {
+        byte_str_each(&text, |_| {}).ok()?;
+        (2, text.len() - 1, false)
+    };
+    Some(ByteStrIter { text, pos, end, raw })
+}
+
+/// Decode the next byte of a `ByteStrIter`.
+pub(crate) fn byte_str_iter_next(iter: &mut ByteStrIter) -> Option<u8> {
+    while iter.pos < iter.end {
+        let s = &iter.text.as_bytes()[iter.pos..iter.end];
+        if iter.raw {
+            iter.pos += 1;
+            return Some(s[0]);
+        }
+        let (b, rest) = byte_str_step(s, iter.pos).ok()?;
+        iter.pos = iter.end - rest.len();
+        if b.is_some() {
+            return b;
+        }
+    }
+    None
+}
+
+/// Decode a non-raw byte string literal, passing each byte of its value to
+/// `f`.
+fn byte_str_each<F: FnMut(u8)>(input: &str, mut f: F) -> Result<(), LiteralError> {
+    if byte(input, 0) != b'b' || byte(input, 1) != b'"' {
+        return Err(LiteralError::NotThisKind);
     }
     // We're going to want to have slices which don't respect codepoint boundaries.
-    let mut s = s[2..].as_bytes();
+    let mut s = &input.as_bytes()[2..];
+    let offset = |s: &[u8]| input.len() - s.len();
+
+    while byte(s, 0) != b'"' {
+        let (b, rest) = byte_str_step(s, offset(s))?;
+        s = rest;
+        if let Some(b) = b {
+            f(b);
+        }
+    }
 
-    let b = match byte(s, 0) {
+    if s != b"\"" {
+        return Err(LiteralError::InvalidSuffix { offset: offset(s) + 1 });
+    }
+    Ok(())
+}
+
+/// Decode the next byte of the body of a non-raw byte string, returning it
+/// along with the remaining input. The byte is `None` if the input began with
+/// a line continuation. The offsets of any errors are relative to `s`, plus
+/// `base`.
+fn byte_str_step(mut s: &[u8], base: usize) -> Result<(Option<u8>, &[u8]), LiteralError> {
+    let invalid = LiteralError::InvalidEscape { offset: base };
+    let byte = match byte(s, 0) {
+        _ if s.is_empty() => return Err(LiteralError::UnterminatedLiteral { offset: base }),
         b'\\' => {
-            let b = byte(s, 1);
+            if s.len() < 2 {
+                return Err(LiteralError::UnterminatedLiteral { offset: base + s.len() });
+            }
+            let b = s[1];
             s = &s[2..];
             match b {
                 b'x' => {
-                    let (rest, b) = backslash_x(s);
+                    let (rest, b) = backslash_x(s).ok_or(invalid)?;
                     s = rest;
                     b
                 }
+                // NOTE: Unicode escapes are not allowed in byte strings.
+                b'u' => return Err(invalid),
                 b'n' => b'\n',
                 b'r' => b'\r',
                 b't' => b'\t',
@@ -329,127 +600,651 @@ pub(crate) fn byte_lit(s: &str) -> Option<u8> {
                 b'0' => b'\0',
                 b'\'' => b'\'',
                 b'"' => b'"',
-                b => {
-                    panic!("unexpected byte {:?} after \\ character in byte literal", b)
+                b'\r' | b'\n' => {
+                    return Ok((None, &s[continuation_len(s)..]));
                 }
+                _ => return Err(invalid),
+            }
+        }
+        b'\r' => {
+            // NOTE: Bare CR is not allowed in byte strings.
+            if byte(s, 1) != b'\n' {
+                return Err(LiteralError::BareCarriageReturn { offset: base });
             }
+            s = &s[2..];
+            b'\n'
         }
         b => {
             s = &s[1..];
             b
         }
     };
+    Ok((Some(byte), s))
+}
 
-    assert!(byte(s, 0) == b'\'');
-    Some(b)
+/// Parse a C string literal, such as `c"a"` or `cr#"a"#`, returning its bytes
+/// followed by a trailing NUL. Unlike byte strings, C strings may contain
+/// unicode characters and `\u` escapes, which are encoded as UTF-8. Interior
+/// NULs, including `\0` escapes, are rejected.
+pub(crate) fn cstr_lit(s: &str) -> Option<Vec<u8>> {
+    try_cstr_lit(s).ok()
 }
 
-pub(crate) fn int_lit(mut s: &str) -> Option<IntLit> {
-    let base = match (byte(s, 0), byte(s, 1)) {
-        (b'0', b'x') => {
-            s = &s[2..];
-            16
-        }
-        (b'0', b'o') => {
-            s = &s[2..];
-            8
-        }
-        (b'0', b'b') => {
-            s = &s[2..];
-            2
-        }
-        (b'0'...b'9', _) => 10,
-        _ => return None,
+/// Like `cstr_lit`, but describes why the literal is malformed. An interior
+/// NUL is reported as an `InvalidCodePoint` at the char or escape which
+/// produced it.
+pub(crate) fn try_cstr_lit(input: &str) -> Result<Vec<u8>, LiteralError> {
+    let raw = match (byte(input, 0), byte(input, 1)) {
+        (b'c', b'"') => false,
+        (b'c', b'r') => true,
+        _ => return Err(LiteralError::NotThisKind),
     };
+    let mut s = input;
+    let offset = |s: &str| input.len() - s.len();
 
-    let mut value: Option<RawInt> = Some(0);
-    loop {
-        let b = byte(s, 0);
-        let digit = match b {
-            b'0'...b'9' => (b - b'0') as RawInt,
-            b'a'...b'f' if base > 10 => 10 + (b - b'a') as RawInt,
-            b'A'...b'F' if base > 10 => 10 + (b - b'A') as RawInt,
-            b'_' => {
-                s = &s[1..];
-                continue;
+    let mut out = Vec::new();
+    let mut nul = None;
+    if raw {
+        let body = raw_str(input)?;
+        let start = 3 + input[2..].bytes().take_while(|&b| b == b'#').count();
+        nul = body.bytes().position(|b| b == 0).map(|idx| start + idx);
+        out.extend_from_slice(body.as_bytes());
+    } else {
+        s = &s[2..];
+        loop {
+            let start = offset(s);
+            match byte(s, 0) {
+                _ if s.is_empty() => {
+                    return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
+                }
+                b'"' => break,
+                b'\\' if byte(s, 1) == b'x' => {
+                    // NOTE: Unlike in strings, any byte may be escaped.
+                    let invalid = LiteralError::InvalidEscape { offset: start };
+                    let (rest, b) = backslash_x(&s[2..]).ok_or(invalid)?;
+                    s = rest;
+                    out.push(b);
+                }
+                b'\\' if byte(s, 1) == b'\r' || byte(s, 1) == b'\n' => {
+                    s = &s[2..];
+                    s = &s[continuation_len(s)..];
+                }
+                b'\\' => {
+                    let (ch, rest) = char_escape(s, start)?;
+                    s = rest;
+                    out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                b'\r' => {
+                    if byte(s, 1) != b'\n' {
+                        return Err(LiteralError::BareCarriageReturn { offset: start });
+                    }
+                    s = &s[2..];
+                    out.push(b'\n');
+                }
+                _ => {
+                    let ch = next_chr(s);
+                    s = &s[ch.len_utf8()..];
+                    out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+            }
+            if nul.is_none() && out.last() == Some(&0) {
+                nul = Some(start);
             }
-            // NOTE: Looking at a floating point literal, we don't want to
-            // consider these integers.
-            b'.' if base == 10 => return None,
-            b'e' | b'E' if base == 10 => return None,
-            _ => break,
-        };
-
-        if digit >= base {
-            panic!("Unexpected digit {:x} out of base range", digit);
         }
-
-        value = value
-            .and_then(|v| v.checked_mul(base))
-            .and_then(|v| v.checked_add(digit));
-        s = &s[1..];
+        if s != "\"" {
+            return Err(LiteralError::InvalidSuffix { offset: offset(s) + 1 });
+        }
     }
 
-    // Check if the suffix is one of our legal suffixes, if it is, return an
-    // equal 'static string which we can store in the IntLit object.
-    let suffix = match s {
-        "u8" => "u8",
-        "i8" => "i8",
-        "u16" => "u16",
-        "i16" => "i16",
-        "u32" => "u32",
-        "i32" => "i32",
-        "u64" => "u64",
-        "i64" => "i64",
-        "usize" => "usize",
-        "isize" => "isize",
-        "" => "",
-        _ => return None,
-    };
-
-    assert_eq!(suffix, s);
+    if let Some(offset) = nul {
+        return Err(LiteralError::InvalidCodePoint { offset });
+    }
+    out.push(0);
+    Ok(out)
+}
 
-    Some(IntLit {
-        val: value,
-        suffix: suffix,
-    })
+pub(crate) fn char_lit(input: &str) -> Result<char, LiteralError> {
+    char_lit_escape(input).map(|c| c.val)
 }
 
-pub(crate) fn float_lit(input: String) -> Option<FloatLit> {
-    match (byte(&input, 0), byte(&input, 1)) {
-        (b'0', b'x') | (b'0', b'o') | (b'0', b'b') => return None,
-        (b'0'...b'9', _) => {}
-        _ => return None,
-    };
+/// Like `char_lit`, but also records how the char was escaped.
+pub(crate) fn char_lit_escape(input: &str) -> Result<CharLit, LiteralError> {
+    let mut s = input;
+    let offset = |s: &str| input.len() - s.len();
 
-    // Rust's floating point literals are very similar to the ones parsed by the
-    // standard library, except that rust's literals can contain ignorable
-    // underscores. Let's remove those underscores in-place.
-    let input = string_filter(input, b'_');
-    let mut s = &input[..];
+    if byte(s, 0) != b'\'' {
+        return Err(LiteralError::NotThisKind);
+    }
+    s = &s[1..];
+
+    let (val, escape) = match byte(s, 0) {
+        _ if s.is_empty() => {
+            return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
+        }
+        b'\\' => {
+            let escape = escape_kind(byte(s, 1));
+            let (ch, rest) = char_escape(s, offset(s))?;
+            s = rest;
+            (ch, escape)
+        }
+        _ => {
+            let ch = next_chr(s);
+            s = &s[ch.len_utf8()..];
+            (ch, EscapeKind::None)
+        }
+    };
+
+    match s.find('\'') {
+        Some(0) if s.len() == 1 => Ok(CharLit { val, escape }),
+        Some(0) => Err(LiteralError::InvalidSuffix { offset: offset(s) + 1 }),
+        Some(_) => Err(LiteralError::MultipleCharacters { offset: offset(s) }),
+        None => Err(LiteralError::UnterminatedLiteral { offset: input.len() }),
+    }
+}
+
+/// Get the kind of escape which begins with `\` followed by the given byte.
+fn escape_kind(b: u8) -> EscapeKind {
+    match b {
+        b'x' => EscapeKind::Hex,
+        b'u' => EscapeKind::Unicode,
+        b'n' | b'r' | b't' | b'0' => EscapeKind::Named,
+        _ => EscapeKind::Ascii,
+    }
+}
+
+pub(crate) fn byte_lit(s: &str) -> Option<u8> {
+    byte_lit_escape(s).map(|b| b.val)
+}
+
+/// Like `byte_lit`, but also records how the byte was escaped.
+pub(crate) fn byte_lit_escape(s: &str) -> Option<ByteLit> {
+    try_byte_lit_escape(s).ok()
+}
+
+/// Like `byte_lit_escape`, but describes why the literal is malformed. A
+/// non-ASCII character is more than one byte, so is reported as
+/// `MultipleCharacters`.
+pub(crate) fn try_byte_lit_escape(input: &str) -> Result<ByteLit, LiteralError> {
+    if byte(input, 0) != b'b' || byte(input, 1) != b'\'' {
+        return Err(LiteralError::NotThisKind);
+    }
+    let mut s = &input[2..];
+    let offset = |s: &str| input.len() - s.len();
+    let invalid = LiteralError::InvalidEscape { offset: 2 };
+
+    let (val, escape) = match byte(s, 0) {
+        _ if s.is_empty() => {
+            return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
+        }
+        b'\\' => {
+            if s.len() < 2 {
+                return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
+            }
+            let b = byte(s, 1);
+            let val = match b {
+                b'x' => {
+                    let (rest, b) = backslash_x(&s[2..]).ok_or(invalid)?;
+                    s = rest;
+                    b
+                }
+                // NOTE: Unicode escapes are not allowed in byte literals.
+                b'u' => return Err(invalid),
+                b'n' => b'\n',
+                b'r' => b'\r',
+                b't' => b'\t',
+                b'\\' => b'\\',
+                b'0' => b'\0',
+                b'\'' => b'\'',
+                b'"' => b'"',
+                _ => return Err(invalid),
+            };
+            if b != b'x' {
+                s = &s[2..];
+            }
+            (val, escape_kind(b))
+        }
+        // NOTE: An unescaped quote ends the literal, so cannot be its value.
+        b'\'' if s.len() == 1 => {
+            return Err(LiteralError::UnterminatedLiteral { offset: input.len() });
+        }
+        b'\'' => return Err(invalid),
+        b if b.is_ascii() => {
+            s = &s[1..];
+            (b, EscapeKind::None)
+        }
+        _ => return Err(LiteralError::MultipleCharacters { offset: 2 }),
+    };
+
+    match s.find('\'') {
+        Some(0) if s.len() == 1 => Ok(ByteLit { val, escape }),
+        Some(0) => Err(LiteralError::InvalidSuffix { offset: offset(s) + 1 }),
+        Some(_) => Err(LiteralError::MultipleCharacters { offset: offset(s) }),
+        None => Err(LiteralError::UnterminatedLiteral { offset: input.len() }),
+    }
+}
+
+/// Check if the suffix is one of our legal integer suffixes, if it is, return
+/// an equal 'static string which we can store in the IntLit object.
+pub(crate) fn int_suffix(s: &str) -> Option<&'static str> {
+    Some(match s {
+        "u8" => "u8",
+        "i8" => "i8",
+        "u16" => "u16",
+        "i16" => "i16",
+        "u32" => "u32",
+        "i32" => "i32",
+        "u64" => "u64",
+        "i64" => "i64",
+        #[cfg(feature = "i128")]
+        "u128" => "u128",
+        #[cfg(feature = "i128")]
+        "i128" => "i128",
+        "usize" => "usize",
+        "isize" => "isize",
+        "" => "",
+        _ => return None,
+    })
+}
+
+/// Check if the suffix is one of our legal float suffixes, if it is, return an
+/// equal 'static string which we can store in the FloatLit object.
+pub(crate) fn float_suffix(s: &str) -> Option<&'static str> {
+    Some(match s {
+        "f32" => "f32",
+        "f64" => "f64",
+        "" => "",
+        _ => return None,
+    })
+}
+
+/// Rejects radixes which `Radix::new` does not accept.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Radix {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Radix, D::Error> {
+        let radix = u32::deserialize(d)?;
+        Radix::new(radix).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Unsigned(radix.into()),
+                                     &"a radix from 3 to 36 without a prefix")
+        })
+    }
+}
+
+/// The serialized form of an `IntLit`, with an owned suffix.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct IntLitRepr {
+    val: Option<RawInt>,
+    digits: String,
+    #[serde(default)]
+    separators: Vec<usize>,
+    suffix: String,
+    #[serde(default)]
+    suffix_offset: Option<usize>,
+    base: IntBase,
+    #[serde(default)]
+    negative: bool,
+}
+
+/// Deserializes the suffix by mapping it back to the equal 'static string,
+/// rejecting unknown suffixes.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for IntLit {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<IntLit, D::Error> {
+        let repr = IntLitRepr::deserialize(d)?;
+        match int_suffix(&repr.suffix) {
+            Some(suffix) => Ok(IntLit {
+                val: repr.val,
+                digits: repr.digits,
+                separators: repr.separators,
+                suffix: Cow::Borrowed(suffix),
+                suffix_offset: repr.suffix_offset,
+                base: repr.base,
+                negative: repr.negative,
+            }),
+            None => Err(de::Error::invalid_value(de::Unexpected::Str(&repr.suffix),
+                                                 &"an integer suffix")),
+        }
+    }
+}
+
+/// The serialized form of a `FloatRepr`, with each part of the digits owned.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct FloatReprData {
+    mantissa: String,
+    has_dot: bool,
+    exp_marker: Option<char>,
+    exp_sign: Option<char>,
+    exp_digits: String,
+}
+
+#[cfg(feature = "serde")]
+impl FloatReprData {
+    /// Join the parts of the digits back together.
+    fn text(&self) -> String {
+        let mut text = self.mantissa.clone();
+        text.extend(self.exp_marker);
+        text.extend(self.exp_sign);
+        text.push_str(&self.exp_digits);
+        text
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<FloatRepr> for FloatReprData {
+    fn from(repr: FloatRepr) -> FloatReprData {
+        FloatReprData {
+            mantissa: repr.mantissa().to_string(),
+            has_dot: repr.has_dot(),
+            exp_marker: repr.exp_marker(),
+            exp_sign: repr.exp_sign(),
+            exp_digits: repr.exp_digits().to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<FloatReprData> for FloatRepr {
+    type Error = &'static str;
+    fn try_from(data: FloatReprData) -> Result<FloatRepr, &'static str> {
+        FloatRepr::new(&data.text()).ok_or("float digits longer than 64 bytes")
+    }
+}
+
+/// The serialized form of a `FloatLit`, with an owned suffix.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct FloatLitRepr {
+    val: f64,
+    suffix: String,
+    #[serde(default)]
+    suffix_offset: Option<usize>,
+    #[serde(default)]
+    repr: Option<FloatReprData>,
+}
+
+/// Deserializes the suffix by mapping it back to the equal 'static string,
+/// rejecting unknown suffixes. If the digits were not recorded, they are
+/// taken from the value.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for FloatLit {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<FloatLit, D::Error> {
+        let repr = FloatLitRepr::deserialize(d)?;
+        match float_suffix(&repr.suffix) {
+            Some(suffix) => {
+                let digits = match repr.repr {
+                    Some(ref data) => data.text(),
+                    None => float_repr_of(repr.val).to_string(),
+                };
+                Ok(float_with_digits(repr.val, suffix, repr.suffix_offset, &digits))
+            }
+            None => Err(de::Error::invalid_value(de::Unexpected::Str(&repr.suffix),
+                                                 &"a float suffix")),
+        }
+    }
+}
+
+pub(crate) fn int_lit(input: &str) -> Result<IntLit, LiteralError> {
+    int_lit_with(input, false, None)
+}
+
+/// Like `int_lit`, but also accepts the uppercase base prefixes `0X`, `0O` and
+/// `0B`, which rustc rejects, and a leading `-` or `+` sign.
+pub(crate) fn int_lit_lenient(input: &str) -> Result<IntLit, LiteralError> {
+    with_sign(input, |s| int_lit_with(s, true, None))
+}
+
+/// Like `int_lit`, but accepts only the given suffixes, which need not be Rust
+/// integer types, instead of the Rust integer suffixes.
+pub(crate) fn int_lit_with_suffixes(input: &str, allowed: &[&str]) -> Result<IntLit, LiteralError> {
+    int_lit_with(input, false, Some(allowed))
+}
+
+fn int_lit_with(input: &str,
+                lenient: bool,
+                allowed: Option<&[&str]>) -> Result<IntLit, LiteralError> {
+    let mut s = input;
+    let offset = |s: &str| input.len() - s.len();
+
+    let prefix = if lenient {
+        byte(s, 1).to_ascii_lowercase()
+    } else {
+        byte(s, 1)
+    };
+    let int_base = match (byte(s, 0), prefix) {
+        (b'0', b'x') => {
+            s = &s[2..];
+            IntBase::Hexadecimal
+        }
+        (b'0', b'o') => {
+            s = &s[2..];
+            IntBase::Octal
+        }
+        (b'0', b'b') => {
+            s = &s[2..];
+            IntBase::Binary
+        }
+        (b'0'..=b'9', _) => IntBase::Decimal,
+        _ => return Err(LiteralError::NotThisKind),
+    };
+    let base = int_base.radix();
+    let (value, digits, separators, rest) = int_digits(input, s, base)?;
+    s = rest;
+    // NOTE: A base prefix must be followed by at least one digit, so `0x` and
+    // `0x_` are not integers with the value 0.
+    if digits.is_empty() {
+        return Err(LiteralError::MissingDigits { offset: offset(s) });
+    }
+    match byte(s, 0) {
+        // NOTE: Looking at a floating point literal, we don't want to
+        // consider these integers.
+        b'.' if base == 10 => return Err(LiteralError::NotThisKind),
+        // A custom suffix, such as `em`, may also start with an `e`.
+        b'e' | b'E' if base == 10 && !allowed.is_some_and(|a| a.contains(&s)) => {
+            return Err(LiteralError::NotThisKind);
+        }
+        _ => {}
+    }
+
+    let suffix = match allowed {
+        None => int_suffix(s).map(Cow::Borrowed),
+        Some(allowed) if s.is_empty() || allowed.contains(&s) => {
+            Some(int_suffix(s).map_or_else(|| Cow::Owned(s.to_string()), Cow::Borrowed))
+        }
+        Some(_) => None,
+    };
+    let suffix = match suffix {
+        Some(suffix) => suffix,
+        // NOTE: Looking at a floating point literal with a suffix, such as
+        // `5f32`, we don't want to consider these integers.
+        None if base == 10 && float_suffix(s).is_some() => {
+            return Err(LiteralError::NotThisKind);
+        }
+        None => return Err(LiteralError::InvalidSuffix { offset: offset(s) }),
+    };
+    Ok(IntLit {
+        val: value,
+        digits,
+        separators,
+        suffix,
+        suffix_offset: if s.is_empty() { None } else { Some(offset(s)) },
+        base: int_base,
+        negative: false,
+    })
+}
+
+/// The digits of an integer, as read by `int_digits`: the value, or `None` if
+/// it overflows, the digits, the positions of the `_` separators among them,
+/// and the remaining input.
+type IntDigits<'a> = (Option<RawInt>, String, Vec<usize>, &'a str);
+
+/// Read the digits of an integer in the given radix, which is at most 36, from
+/// the start of `s`, skipping `_` separators. A letter which is not a digit in
+/// the radix ends the digits, as it may start a suffix. Offsets in errors are
+/// relative to the start of `input`.
+fn int_digits<'a>(input: &str, mut s: &'a str, radix: u32) -> Result<IntDigits<'a>, LiteralError> {
+    let base = radix as RawInt;
+    let mut value: Option<RawInt> = Some(0);
+    let mut digits = String::new();
+    let mut separators = Vec::new();
+    loop {
+        let b = byte(s, 0);
+        let digit = match (b, digit_val(b)) {
+            (_, Some(digit)) => digit as RawInt,
+            (b'_', _) => {
+                separators.push(digits.len());
+                s = &s[1..];
+                continue;
+            }
+            _ => break,
+        };
+
+        if digit >= base {
+            if b.is_ascii_alphabetic() {
+                break;
+            }
+            return Err(LiteralError::InvalidDigit { offset: input.len() - s.len() });
+        }
+
+        value = value
+            .and_then(|v| v.checked_mul(base))
+            .and_then(|v| v.checked_add(digit));
+        digits.push(b as char);
+        s = &s[1..];
+    }
+    Ok((value, digits, separators, s))
+}
+
+/// Parse the digits of an integer in any radix from 2 to 36, without a base
+/// prefix or suffix, such as `zz` in base 36.
+pub(crate) fn int_radix_lit(input: &str, radix: u32) -> Result<IntLit, LiteralError> {
+    let base = match IntBase::from_radix(radix) {
+        Some(base) => base,
+        None => return Err(LiteralError::NotThisKind),
+    };
+    let (val, digits, separators, rest) = int_digits(input, input, radix)?;
+    if !rest.is_empty() {
+        return Err(LiteralError::InvalidDigit { offset: input.len() - rest.len() });
+    }
+    if digits.is_empty() {
+        return Err(LiteralError::MissingDigits { offset: input.len() });
+    }
+    Ok(IntLit {
+        val,
+        digits,
+        separators,
+        suffix: Cow::Borrowed(""),
+        suffix_offset: None,
+        base,
+        negative: false,
+    })
+}
+
+/// Like `int_lit`, but accepts a leading `-` or `+` sign. `Literal` tokens
+/// never contain a sign, so this is only useful for text where the sign has
+/// been joined onto the literal, such as a `syn::LitInt` built from `"-5"`.
+pub(crate) fn signed_int_lit(input: &str) -> Option<IntLit> {
+    with_sign(input, int_lit).ok()
+}
+
+/// Strip a leading `-` or `+` sign from the input, and parse the rest with
+/// `parse`, recording the sign on the literal. The offsets of the suffix and
+/// of any errors are relative to the input, including the sign.
+fn with_sign<F>(input: &str, parse: F) -> Result<IntLit, LiteralError>
+    where F: FnOnce(&str) -> Result<IntLit, LiteralError>
+{
+    let (negative, s) = match byte(input, 0) {
+        b'-' => (true, &input[1..]),
+        b'+' => (false, &input[1..]),
+        _ => (false, input),
+    };
+    let sign = input.len() - s.len();
+    match parse(s) {
+        Ok(i) => Ok(IntLit {
+            negative,
+            suffix_offset: i.suffix_offset.map(|o| o + sign),
+            ..i
+        }),
+        Err(err) => Err(shift_error(err, sign)),
+    }
+}
+
+/// Move the offset of the error, if it has one, `by` bytes later.
+fn shift_error(err: LiteralError, by: usize) -> LiteralError {
+    use LiteralError::*;
+    match err {
+        NotThisKind => NotThisKind,
+        InvalidEscape { offset } => InvalidEscape { offset: offset + by },
+        InvalidCodePoint { offset } => InvalidCodePoint { offset: offset + by },
+        BareCarriageReturn { offset } => BareCarriageReturn { offset: offset + by },
+        UnterminatedLiteral { offset } => UnterminatedLiteral { offset: offset + by },
+        InvalidDigit { offset } => InvalidDigit { offset: offset + by },
+        MissingDigits { offset } => MissingDigits { offset: offset + by },
+        MultipleCharacters { offset } => MultipleCharacters { offset: offset + by },
+        InvalidSuffix { offset } => InvalidSuffix { offset: offset + by },
+        Overflow { offset } => Overflow { offset: offset + by },
+    }
+}
+
+/// Build a floating point literal from the decimal digits and suffix of a
+/// token which has already been lexed, such as a `syn::LitFloat`, along with
+/// the token's text.
+#[cfg(feature = "syn")]
+pub(crate) fn float_from_parts(digits: &str, suffix: &str, text: &str) -> Option<FloatLit> {
+    Some(float_with_digits(digits.parse().ok()?,
+                           float_suffix(suffix)?,
+                           suffix_offset(suffix, text),
+                           text.get(..text.len() - suffix.len())?))
+}
+
+/// The suffix always ends the token, so it starts `suffix.len()` bytes before
+/// the end of its text.
+#[cfg(feature = "syn")]
+fn suffix_offset(suffix: &str, text: &str) -> Option<usize> {
+    if suffix.is_empty() {
+        None
+    } else {
+        Some(text.len() - suffix.len())
+    }
+}
+
+/// Parse a floating point literal. Rust has no hexadecimal, octal or binary
+/// floats, so literals such as `0x1.8p3` are intentionally rejected, and
+/// `0xAf32` is the integer `0xAF32`. Support for other languages' hex floats
+/// should be exposed as a separate function, rather than added here.
+pub(crate) fn float_lit(input: &str) -> Result<FloatLit, LiteralError> {
+    match (byte(input, 0), byte(input, 1)) {
+        (b'0', b'x') | (b'0', b'o') | (b'0', b'b') => return Err(LiteralError::NotThisKind),
+        (b'0'..=b'9', _) => {}
+        _ => return Err(LiteralError::NotThisKind),
+    };
+    let mut s = input;
+    let offset = |s: &str| input.len() - s.len();
 
     let mut has_dot = false;
     let mut has_exp = false;
     loop {
         match byte(s, 0) {
-            b'0'...b'9' => {
+            b'0'..=b'9' | b'_' => {
                 s = &s[1..];
             }
             b'.' => {
-                s = &s[1..];
                 if has_dot {
-                    panic!("Unexpected second dot while parsing float literal");
+                    return Err(LiteralError::InvalidSuffix { offset: offset(s) });
                 }
+                s = &s[1..];
                 has_dot = true;
             }
             b'e' | b'E' => {
                 s = &s[1..];
+                // The exponent may have a single sign, which must come before
+                // any digits or underscores.
+                if byte(s, 0) == b'+' || byte(s, 0) == b'-' {
+                    s = &s[1..];
+                }
                 loop {
                     match byte(s, 0) {
-                        b'+' | b'-' if !has_exp => {
-                            s = &s[1..];
-                        }
-                        b'0'...b'9' => {
+                        b'0'..=b'9' => {
                             s = &s[1..];
                             has_exp = true;
                         }
@@ -459,32 +1254,250 @@ pub(crate) fn float_lit(input: String) -> Option<FloatLit> {
                         _ => break,
                     }
                 }
-                assert!(has_exp,
-                        "Unexpected end of float literal after `E` char");
+                if !has_exp {
+                    return Err(LiteralError::MissingDigits { offset: offset(s) });
+                }
                 break;
             }
             _ => break,
         };
     }
 
-    let suffix = match s {
-        "f32" => "f32",
-        "f64" => "f64",
-        "" => "",
-        _ => return None,
+    let suffix = match float_suffix(s) {
+        Some(suffix) => suffix,
+        // If we don't have an exponent or a ., then we're looking at an
+        // integer literal. Don't parse it as a float.
+        None if !has_exp && !has_dot => return Err(LiteralError::NotThisKind),
+        None => return Err(LiteralError::InvalidSuffix { offset: offset(s) }),
     };
-    assert_eq!(suffix, s);
+    if !has_exp && !has_dot && suffix.is_empty() {
+        return Err(LiteralError::NotThisKind);
+    }
 
-    // If we don't have an exponent or a . and the suffix is empty, then we're
-    // looking at an integer literal. Don't parse it as a float.
-    if !has_exp && !has_dot && suffix == "" {
-        return None;
+    // Rust's floating point literals are very similar to the ones parsed by the
+    // standard library, except that rust's literals can contain ignorable
+    // underscores. Let's remove those underscores in-place.
+    let digits = string_filter(input[..offset(s)].to_string(), b'_');
+    // NOTE: The digits have been validated above, so this shouldn't fail, but
+    // report an error rather than panicking if they are ever rejected.
+    let val = match digits.parse::<f64>() {
+        Ok(val) => val,
+        Err(_) => return Err(LiteralError::InvalidSuffix { offset: offset(s) }),
+    };
+    let suffix_offset = if suffix.is_empty() { None } else { Some(offset(s)) };
+    Ok(float_with_digits(val, suffix, suffix_offset, &input[..offset(s)]))
+}
+
+/// Get the value of an integer literal, or failing that a float literal, as an
+/// `f64`, whatever its suffix.
+pub(crate) fn number_f64<F>(int: Option<IntLit>, float: F) -> Option<f64>
+    where F: FnOnce() -> Option<FloatLit>
+{
+    match int {
+        Some(int) => int.to_float_lossy(),
+        None => float().map(|f| f.val),
     }
+}
 
-    Some(FloatLit {
-        val: input[..input.len() - suffix.len()].parse::<f64>().unwrap(),
-        suffix: suffix,
-    })
+/// Convert the integer to the nearest `f64`. Decimal integers are rounded
+/// correctly, however large they are, while integers in other bases must fit
+/// in the integer type used internally. If `exact` is set, returns `None`
+/// unless the `f64` is exactly equal to the integer.
+pub(crate) fn int_f64(int: &IntLit, exact: bool) -> Option<f64> {
+    let val = if int.base == IntBase::Decimal {
+        let val = int.digits.parse::<f64>().ok()?;
+        if exact && !float_is_exact(&int.digits, val) {
+            return None;
+        }
+        val
+    } else {
+        let raw = int.val?;
+        let val = raw as f64;
+        // NOTE: The cast back saturates, so a value which was rounded up to
+        // `2^bits` must be rejected first.
+        if exact && !(val < RawInt::MAX as f64 && val as RawInt == raw) {
+            return None;
+        }
+        val
+    };
+    Some(if int.negative { -val } else { val })
+}
+
+/// Returns `true` if the integer literal, or failing that the float literal,
+/// has a suffix.
+pub(crate) fn is_suffixed<F>(int: Option<IntLit>, float: F) -> bool
+    where F: FnOnce() -> Option<FloatLit>
+{
+    match int {
+        Some(int) => !int.suffix.is_empty(),
+        None => matches!(float(), Some(ref f) if !f.suffix.is_empty()),
+    }
+}
+
+/// Recognize the text which `f64`'s `Display` writes for values without a
+/// literal form: `inf`, `-inf` and `NaN`.
+pub(crate) fn float_special(input: &str) -> Option<f64> {
+    match input {
+        "inf" => Some(f64::INFINITY),
+        "-inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        _ => None,
+    }
+}
+
+/// Like `float_lit`, but also accepts an unsuffixed decimal integer, such as
+/// `1`, which some backends emit for floats with integral values.
+pub(crate) fn float_lit_forgiving(input: &str) -> Result<FloatLit, LiteralError> {
+    match float_lit(input) {
+        Err(LiteralError::NotThisKind) => {}
+        res => return res,
+    }
+    let int = int_lit(input)?;
+    if int.int_base() != IntBase::Decimal || !int.suffix().is_empty() {
+        return Err(LiteralError::NotThisKind);
+    }
+    // NOTE: A decimal integer's digits are always a valid float.
+    let val = match int.raw_digits().parse::<f64>() {
+        Ok(val) => val,
+        Err(_) => unreachable!("decimal digits {:?} failed to parse", int.raw_digits()),
+    };
+    Ok(float_with_digits(val, "", None, input))
+}
+
+/// Build a floating point literal, recording how its digits were written.
+/// Digits which are too long to be kept in a `FloatRepr` are replaced with the
+/// shortest form which round-trips, so `is_exact` and `decompose` are computed
+/// from them now.
+pub(crate) fn float_with_digits(val: f64,
+                                suffix: &'static str,
+                                suffix_offset: Option<usize>,
+                                digits: &str)
+                                -> FloatLit {
+    let mut lit = FloatLit {
+        val,
+        suffix,
+        suffix_offset,
+        repr: float_repr_of(val),
+        long: None,
+    };
+    match FloatRepr::new(digits) {
+        Some(repr) => lit.repr = repr,
+        None => {
+            lit.long = Some(LongDigits {
+                exact: float_is_exact(digits, lit.suffix_val()),
+                decomposed: float_decompose(digits),
+            })
+        }
+    }
+    lit
+}
+
+/// Split the digits of a floating point literal, which have already been
+/// validated, into the mantissa, the exponent sign, and the exponent digits.
+fn float_parts(digits: &str) -> (&str, Option<u8>, &str) {
+    let (mantissa, exp) = match digits.find(['e', 'E']) {
+        Some(idx) => (&digits[..idx], &digits[idx + 1..]),
+        None => (digits, ""),
+    };
+    match byte(exp, 0) {
+        b @ b'+' | b @ b'-' => (mantissa, Some(b), &exp[1..]),
+        _ => (mantissa, None, exp),
+    }
+}
+
+/// Check if the decimal number written in the digits is exactly equal to the
+/// value. Every finite binary float has a finite decimal expansion, so this
+/// compares the written digits against that expansion, with both normalized
+/// to a string of significant digits and a decimal exponent.
+pub(crate) fn float_is_exact(text: &str, val: f64) -> bool {
+    if !val.is_finite() {
+        return false;
+    }
+    let (mantissa, exp_sign, exp_digits) = float_parts(text);
+
+    // The written number is `0.{digits} * 10^exp`.
+    let mut digits = String::new();
+    let mut int_len = 0;
+    for c in mantissa.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            '.' => int_len = digits.len(),
+            _ => {}
+        }
+    }
+    if !mantissa.contains('.') {
+        int_len = digits.len();
+    }
+    let leading = digits.len() - digits.trim_start_matches('0').len();
+    let written = digits[leading..].trim_end_matches('0');
+    if written.is_empty() || val == 0.0 {
+        return written.is_empty() && val == 0.0;
+    }
+    let exp = string_filter(exp_digits.to_string(), b'_').parse::<i64>().ok();
+    let exp = match (exp, exp_sign) {
+        (Some(exp), Some(b'-')) => -exp,
+        (Some(exp), _) => exp,
+        // NOTE: An exponent this large overflows or underflows any float.
+        (None, _) if exp_digits.is_empty() => 0,
+        (None, _) => return false,
+    };
+    let written_exp = exp.saturating_add(int_len as i64).saturating_sub(leading as i64);
+
+    // NOTE: No `f64` has more than 767 significant decimal digits, so this
+    // formats the value exactly, as `d.ddd...e{exp}`.
+    let mut exact = String::new();
+    let _ = fmt::Write::write_fmt(&mut exact, format_args!("{:.800e}", val.abs()));
+    let (mantissa, exact_exp) = match exact.find('e') {
+        Some(idx) => (&exact[..idx], &exact[idx + 1..]),
+        None => return false,
+    };
+    let exact_digits = string_filter(mantissa.to_string(), b'.');
+    let exact_exp = match exact_exp.parse::<i64>() {
+        Ok(exp) => exp + 1,
+        Err(_) => return false,
+    };
+    written == exact_digits.trim_end_matches('0') && written_exp == exact_exp
+}
+
+/// Decompose the decimal number written in the digits into an integer
+/// mantissa and a power of ten, without rounding. Returns `None` if either
+/// does not fit.
+pub(crate) fn float_decompose(text: &str) -> Option<(i128, i32)> {
+    let (mantissa_digits, exp_sign, exp_digits) = float_parts(text);
+    let mut mantissa: i128 = 0;
+    let mut frac_len: i32 = 0;
+    let mut in_frac = false;
+    for b in mantissa_digits.bytes() {
+        match b {
+            b'0'..=b'9' => {
+                mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+                if in_frac {
+                    frac_len = frac_len.checked_add(1)?;
+                }
+            }
+            b'.' => in_frac = true,
+            _ => {}
+        }
+    }
+    let exp = if exp_digits.is_empty() {
+        0
+    } else {
+        string_filter(exp_digits.to_string(), b'_').parse::<i32>().ok()?
+    };
+    let exp = if exp_sign == Some(b'-') { -exp } else { exp };
+    Some((mantissa, exp.checked_sub(frac_len)?))
+}
+
+/// The digits of a float which was not parsed from text, written in the
+/// shortest form which round-trips.
+pub(crate) fn float_repr_of(val: f64) -> FloatRepr {
+    let mut digits = String::new();
+    // NOTE: Writing to a `String` cannot fail.
+    let _ = fmt::Write::write_fmt(&mut digits, format_args!("{:?}", val));
+    match FloatRepr::new(&digits) {
+        Some(repr) => repr,
+        None => unreachable!("shortest form {:?} of a float is too long", digits),
+    }
 }
 
 pub(crate) fn outer_doc(s: String) -> Option<String> {
@@ -502,3 +1515,395 @@ pub(crate) fn inner_doc(s: String) -> Option<String> {
         None
     }
 }
+
+/// Parse the text `true` or `false`. These are identifiers rather than
+/// literals, so this only matches text which has been stringified from them.
+pub(crate) fn bool_lit(s: &str) -> Option<bool> {
+    match s {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Strip the delimiters from a doc comment of either kind, returning the text
+/// of its body. For block comments, the `*` which is commonly used to align
+/// each line is removed, as are a blank first line and a trailing line which
+/// only contains the indentation of the closing `*/`.
+pub(crate) fn doc_text(s: &str) -> Option<String> {
+    if s.starts_with("////") || s.starts_with("/***") {
+        return None;
+    }
+    if s.starts_with("///") || s.starts_with("//!") {
+        return Some(s[3..].to_string());
+    }
+    if !(s.starts_with("/**") || s.starts_with("/*!")) || s.len() < 5 || !s.ends_with("*/") {
+        return None;
+    }
+
+    let mut lines: Vec<&str> = s[3..s.len() - 2].split('\n').collect();
+    if lines.len() > 1 {
+        if lines[0].trim().is_empty() {
+            lines.remove(0);
+        }
+        if lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        for line in &mut lines {
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix('*') {
+                *line = rest;
+            }
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+/// Like `doc_text`, but also accepts the string literal of a `#[doc = "..."]`
+/// attribute, which is how doc comments are passed to procedural macros.
+pub(crate) fn doc_from_attr(s: &str) -> Option<String> {
+    doc_text(s).or_else(|| str_lit(s).ok())
+}
+
+/// Classify a literal by inspecting its leading bytes. This does not validate
+/// the body of the literal.
+pub(crate) fn lit_kind(s: &str) -> LiteralKind {
+    match (byte(s, 0), byte(s, 1)) {
+        (b'"', _) | (b'r', b'"') | (b'r', b'#') => LiteralKind::Str,
+        (b'b', b'"') | (b'b', b'r') => LiteralKind::ByteStr,
+        (b'c', b'"') | (b'c', b'r') => LiteralKind::CStr,
+        (b'b', b'\'') => LiteralKind::Byte,
+        (b'\'', _) => LiteralKind::Char,
+        (b'0', b'x') | (b'0', b'o') | (b'0', b'b') => LiteralKind::Int,
+        (b'0'..=b'9', _) => {
+            // Skip the integer part, and look at what follows it to determine
+            // if this is a floating point literal.
+            let rest = s.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_');
+            match byte(rest, 0) {
+                b'.' | b'e' | b'E' | b'f' => LiteralKind::Float,
+                _ => LiteralKind::Int,
+            }
+        }
+        _ if s.starts_with("///") || s.starts_with("/**") => LiteralKind::OuterDoc,
+        _ if s.starts_with("//!") || s.starts_with("/*!") => LiteralKind::InnerDoc,
+        _ => LiteralKind::Unknown,
+    }
+}
+
+/// Returns `true` if the text is a lifetime or label, such as `'a`, which is a
+/// `'` followed by an identifier, with no closing quote.
+pub(crate) fn is_lifetime(s: &str) -> bool {
+    s.strip_prefix('\'').is_some_and(is_ident)
+}
+
+/// Returns `true` if the text is an identifier, such as `foo` or `_1`.
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c == '_' || c.is_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c == '_' || c.is_alphanumeric())
+}
+
+/// Split the prefix, such as `b` or `cr`, off a string-like literal, and
+/// report whether it is raw. Returns `None` unless the prefix is followed by
+/// the opening quote, so the raw identifier `r#foo` is not a string. This does
+/// not look at the body of the literal.
+pub(crate) fn str_prefix(s: &str) -> Option<(&str, bool)> {
+    let kind = match byte(s, 0) {
+        b'b' | b'c' => 1,
+        _ => 0,
+    };
+    let raw = byte(s, kind) == b'r';
+    let prefix = kind + raw as usize;
+    let hashes = if raw {
+        s[prefix..].bytes().take_while(|&b| b == b'#').count()
+    } else {
+        0
+    };
+    if byte(s, prefix + hashes) != b'"' {
+        return None;
+    }
+    Some((&s[..prefix], raw))
+}
+
+/// Decode a literal of any kind, trying each parser in turn.
+pub(crate) fn lit_value(s: &str) -> Option<LitValue> {
+    if let Ok(i) = int_lit(s) {
+        // NOTE: rustc rejects integers out of range for their suffix, and so
+        // does `lit_value_of_kind`.
+        if i.declared_overflow() {
+            return None;
+        }
+        return Some(LitValue::Int(i));
+    }
+    if let Ok(f) = float_lit(s) {
+        return Some(LitValue::Float(f));
+    }
+    if let Ok(s) = str_lit(s) {
+        return Some(LitValue::Str(s));
+    }
+    if let Some(b) = byte_str_lit(s) {
+        return Some(LitValue::ByteStr(b));
+    }
+    if let Some(b) = cstr_lit(s) {
+        return Some(LitValue::CStr(b));
+    }
+    if let Ok(c) = char_lit(s) {
+        return Some(LitValue::Char(c));
+    }
+    if let Some(b) = byte_lit(s) {
+        return Some(LitValue::Byte(b));
+    }
+    if let Some(d) = inner_doc(s.to_string()) {
+        return Some(LitValue::InnerDoc(d));
+    }
+    outer_doc(s.to_string()).map(LitValue::OuterDoc)
+}
+
+/// Decode a literal with the parser for the kind reported by `lit_kind`.
+pub(crate) fn lit_value_of_kind(s: &str) -> Result<LitValue, LiteralError> {
+    match lit_kind(s) {
+        LiteralKind::Int => match int_lit(s) {
+            // NOTE: Only suffixed literals can overflow their declared type.
+            Ok(ref i) if i.declared_overflow() => {
+                Err(LiteralError::Overflow { offset: i.suffix_offset().unwrap_or(0) })
+            }
+            res => res.map(LitValue::Int),
+        },
+        LiteralKind::Float => float_lit(s).map(LitValue::Float),
+        LiteralKind::Str => str_lit(s).map(LitValue::Str),
+        LiteralKind::ByteStr => try_byte_str_lit(s).map(LitValue::ByteStr),
+        LiteralKind::CStr => try_cstr_lit(s).map(LitValue::CStr),
+        LiteralKind::Char => char_lit(s).map(LitValue::Char),
+        LiteralKind::Byte => try_byte_lit_escape(s).map(|b| LitValue::Byte(b.val)),
+        // NOTE: `lit_kind` has already checked the comment's prefix, which is
+        // all there is to validate.
+        LiteralKind::InnerDoc => Ok(LitValue::InnerDoc(s.to_string())),
+        LiteralKind::OuterDoc => Ok(LitValue::OuterDoc(s.to_string())),
+        LiteralKind::Unknown => Err(LiteralError::NotThisKind),
+    }
+}
+
+/// Decode a literal of any kind, making a best effort to recover the value of
+/// a malformed string, byte string, char or byte literal, or of an integer
+/// which is out of range for its suffix.
+pub(crate) fn lit_lenient(s: &str) -> (Option<LitValue>, Vec<LiteralDiagnostic>) {
+    let err = match lit_value_of_kind(s) {
+        Ok(value) => return (Some(value), Vec::new()),
+        Err(err) => err,
+    };
+
+    let mut diags = Vec::new();
+    let value = match (lit_kind(s), byte(s, 0)) {
+        (LiteralKind::Str, b'"') => {
+            let units = lenient_body(s, 1, b'"', false, &mut diags);
+            Some(LitValue::Str(units.into_iter().filter_map(char::from_u32).collect()))
+        }
+        (LiteralKind::ByteStr, _) if byte(s, 1) == b'"' => {
+            let units = lenient_body(s, 2, b'"', true, &mut diags);
+            Some(LitValue::ByteStr(units.into_iter().map(|b| b as u8).collect()))
+        }
+        (LiteralKind::Char, _) => {
+            let units = lenient_body(s, 1, b'\'', false, &mut diags);
+            lenient_single(s, &units, &mut diags).and_then(char::from_u32).map(LitValue::Char)
+        }
+        (LiteralKind::Byte, _) => {
+            let units = lenient_body(s, 2, b'\'', true, &mut diags);
+            lenient_single(s, &units, &mut diags).map(|b| LitValue::Byte(b as u8))
+        }
+        (LiteralKind::Int, _) => int_lit(s).ok().map(LitValue::Int),
+        _ => None,
+    };
+    // NOTE: Recovery is only attempted for some kinds of literal, but should
+    // always find the problem when it is.
+    if diags.is_empty() {
+        diags.push(error_diagnostic(s, err));
+    }
+    (value, diags)
+}
+
+fn diagnostic(range: Range<usize>, message: &str) -> LiteralDiagnostic {
+    LiteralDiagnostic { range, message: message.to_string() }
+}
+
+/// Describe the error returned by one of the strict parsers.
+fn error_diagnostic(s: &str, err: LiteralError) -> LiteralDiagnostic {
+    // The end of the char at `offset`, for errors which cover a single char.
+    let char_end = |offset: usize| offset + s[offset..].chars().next().map_or(0, char::len_utf8);
+    match err {
+        LiteralError::NotThisKind => diagnostic(0..s.len(), "not a literal"),
+        LiteralError::InvalidEscape { offset } => {
+            diagnostic(offset..offset + escape_len(&s[offset..]), "invalid escape")
+        }
+        // NOTE: This is the only error which may be reported at a char,
+        // rather than an escape, for a NUL in a C string.
+        LiteralError::InvalidCodePoint { offset } if byte(s, offset) != b'\\' => {
+            diagnostic(offset..char_end(offset), "invalid unicode code point")
+        }
+        LiteralError::InvalidCodePoint { offset } => {
+            diagnostic(offset..offset + escape_len(&s[offset..]), "invalid unicode code point")
+        }
+        LiteralError::BareCarriageReturn { offset } => {
+            diagnostic(offset..char_end(offset), "bare CR not allowed")
+        }
+        LiteralError::UnterminatedLiteral { offset } => {
+            diagnostic(offset..offset, "unterminated literal")
+        }
+        LiteralError::InvalidDigit { offset } => {
+            diagnostic(offset..char_end(offset), "invalid digit for the base")
+        }
+        LiteralError::MissingDigits { offset } => diagnostic(offset..offset, "missing digits"),
+        LiteralError::MultipleCharacters { offset } => {
+            diagnostic(offset..s.len(), "literal contains more than one character")
+        }
+        LiteralError::InvalidSuffix { offset } => diagnostic(offset..s.len(), "invalid suffix"),
+        LiteralError::Overflow { offset } => {
+            diagnostic(offset..s.len(), "integer out of range for its suffix")
+        }
+    }
+}
+
+/// Get the length of the malformed escape sequence at the start of `s`, which
+/// begins with a `\`, so that it can be skipped.
+fn escape_len(s: &str) -> usize {
+    let rest = &s[1..];
+    match byte(rest, 0) {
+        b'x' => 1 + 1 + rest[1..].bytes().take(2).take_while(u8::is_ascii_hexdigit).count(),
+        b'u' if byte(rest, 1) == b'{' => {
+            // Include everything up to the closing `}`, if there is a
+            // plausible one.
+            match rest[2..].bytes().position(|b| !b.is_ascii_hexdigit() && b != b'_') {
+                Some(idx) if byte(rest, 2 + idx) == b'}' => 1 + 2 + idx + 1,
+                _ => 1 + 2,
+            }
+        }
+        _ => 1 + rest.chars().next().map_or(0, char::len_utf8),
+    }
+}
+
+/// Decode the escape sequence at the start of `s`, which begins with a `\`,
+/// returning the char, or byte if `bytes` is set, and the remaining input. If
+/// it is malformed, returns its length and a description of the problem.
+fn lenient_escape(s: &str, bytes: bool) -> Result<(u32, &str), (usize, &'static str)> {
+    if s.len() < 2 {
+        return Err((s.len(), "incomplete escape"));
+    }
+    let b = byte(s, 1);
+    if !bytes {
+        return match char_escape(s, 0) {
+            Ok((ch, rest)) => Ok((ch as u32, rest)),
+            Err(LiteralError::InvalidCodePoint { .. }) => {
+                Err((escape_len(s), "invalid unicode code point"))
+            }
+            Err(_) if b == b'x' => Err((escape_len(s), "invalid hex escape")),
+            Err(_) if b == b'u' => Err((escape_len(s), "invalid unicode escape")),
+            Err(_) => Err((escape_len(s), "unknown escape")),
+        };
+    }
+    let val = match b {
+        b'x' => {
+            return match backslash_x(&s[2..]) {
+                Some((rest, b)) => Ok((b as u32, rest)),
+                None => Err((escape_len(s), "invalid hex escape")),
+            };
+        }
+        b'u' => return Err((escape_len(s), "unicode escape in a byte literal")),
+        b'n' => b'\n',
+        b'r' => b'\r',
+        b't' => b'\t',
+        b'\\' => b'\\',
+        b'0' => b'\0',
+        b'\'' => b'\'',
+        b'"' => b'"',
+        _ => return Err((escape_len(s), "unknown escape")),
+    };
+    Ok((val as u32, &s[2..]))
+}
+
+/// Decode the body of a non-raw string, byte string, char, or byte literal,
+/// from `start` up to the closing `quote`, recording a diagnostic for each
+/// problem and continuing past it. Returns the chars of the body as code
+/// points, or if `bytes` is set, its bytes.
+fn lenient_body(input: &str,
+                start: usize,
+                quote: u8,
+                bytes: bool,
+                diags: &mut Vec<LiteralDiagnostic>) -> Vec<u32> {
+    let mut s = &input[start..];
+    let offset = |s: &str| input.len() - s.len();
+
+    let mut out = Vec::new();
+    loop {
+        match byte(s, 0) {
+            _ if s.is_empty() => {
+                diags.push(diagnostic(input.len()..input.len(), "unterminated literal"));
+                return out;
+            }
+            b if b == quote => break,
+            b'\\' if quote == b'"' && (byte(s, 1) == b'\r' || byte(s, 1) == b'\n') => {
+                s = &s[2..];
+                s = &s[continuation_len(s)..];
+            }
+            b'\\' => match lenient_escape(s, bytes) {
+                Ok((val, rest)) => {
+                    out.push(val);
+                    s = rest;
+                }
+                Err((len, problem)) => {
+                    let mut message = problem.to_string();
+                    message.push_str(" `");
+                    message.push_str(&s[..len]);
+                    message.push('`');
+                    diags.push(LiteralDiagnostic { range: offset(s)..offset(s) + len, message });
+                    if !bytes {
+                        out.push(char::REPLACEMENT_CHARACTER as u32);
+                    }
+                    s = &s[len..];
+                }
+            },
+            b'\r' if byte(s, 1) != b'\n' => {
+                diags.push(diagnostic(offset(s)..offset(s) + 1, "bare CR not allowed"));
+                s = &s[1..];
+            }
+            b'\r' => {
+                out.push('\n' as u32);
+                s = &s[2..];
+            }
+            _ => {
+                let ch = next_chr(s);
+                if bytes {
+                    out.extend(ch.encode_utf8(&mut [0; 4]).bytes().map(|b| b as u32));
+                } else {
+                    out.push(ch as u32);
+                }
+                s = &s[ch.len_utf8()..];
+            }
+        }
+    }
+
+    s = &s[1..];
+    if !s.is_empty() {
+        diags.push(diagnostic(offset(s)..input.len(), "unexpected characters after the literal"));
+    }
+    out
+}
+
+/// Get the value of a char or byte literal, which should have exactly one
+/// char or byte in its body.
+fn lenient_single(s: &str, units: &[u32], diags: &mut Vec<LiteralDiagnostic>) -> Option<u32> {
+    match units.len() {
+        // A malformed escape is dropped, so don't also report the body as
+        // empty.
+        0 if diags.is_empty() => diags.push(diagnostic(0..s.len(), "empty literal")),
+        0 | 1 => {}
+        _ => diags.push(diagnostic(0..s.len(), "literal contains more than one character")),
+    }
+    units.first().cloned()
+}
+
+/// Like `lit_value`, but keeps the text alongside the value.
+pub(crate) fn parsed_lit(text: String) -> Option<ParsedLit> {
+    lit_value(&text).map(|value| ParsedLit { text, value })
+}
@@ -4,31 +4,85 @@
 //!
 //! ## Supported Features
 //!
+//! * `std` **default**: Link against the standard library, and implement
+//!   `std::error::Error` for the error types. Without this feature the crate
+//!   is `no_std`, and only requires `core` and `alloc`.
+//!
 //! * `i128`: Add support for interpreting the `i128` and `u128` integer types.
-//!   *nightly only*
 //!
-//! * `proc-macro2` **default**: Implement `LiteralExt` on `proc_macro2::Literal`.
+//! * `proc-macro2` **default**: Implement `LiteralExt` on `proc_macro2::Literal`,
+//!   and `LiteralExtSpanned` on `proc_macro2::TokenTree`, and export
+//!   `literals`, which finds the literals in a `proc_macro2::TokenStream`.
 //!
-//! * `proc-macro`: Implement `LiteralExt` on `proc_macro::Literal`.
-//!   *nightly only*
+//! * `proc-macro`: Implement `LiteralExt` and `LiteralExtSpanned` on
+//!   `proc_macro::Literal`.
 //!
 //! * `dummy`: Export a type `DummyLiteral` with a public constructor
 //!   which implements the `LiteralExt` trait.
+//!
+//! * `intern`: Add `LiteralExt::parse_interned_string`, which shares the
+//!   decoded values of identical string literals through a thread-local
+//!   interner, rather than allocating each one.
+//!
+//! * `syn`: Implement `LiteralExt` on `syn::Lit`.
+//!
+//! * `num-bigint`: Add `IntLit::as_bigint`, for integers of any size.
+//!
+//! * `serde`: Implement `Serialize` and `Deserialize` for `IntLit` and
+//!   `FloatLit`.
+//!
+//! ## Parsing Text Directly
+//!
+//! The functions in the `parse` module interpret the source text of a literal
+//! directly, and are available regardless of which features are enabled. The
+//! `escape` module exposes the escape sequence decoder used by the string and
+//! char parsers, a tokenizer which steps through a string literal's body one
+//! escape at a time, and encoders which write values as literals.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-#![cfg_attr(feature = "i128", feature(i128_type))]
-#![cfg_attr(feature = "proc-macro", feature(proc_macro))]
+#[cfg(any(feature = "std", test))]
+extern crate core;
+extern crate alloc;
 
+// NOTE: `proc_macro` is stable, but must still be declared, as it is only
+// implicitly available to `proc-macro` crates.
 #[cfg(feature = "proc-macro")]
 extern crate proc_macro;
 
 #[cfg(feature = "proc-macro2")]
 extern crate proc_macro2;
 
-#[cfg(feature = "dummy")]
-use std::fmt;
+#[cfg(feature = "num-bigint")]
+extern crate num_bigint;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "syn")]
+extern crate syn;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, Range};
+use core::str::{self, FromStr};
+#[cfg(feature = "std")]
+use std::error;
+#[cfg(feature = "intern")]
+use std::sync::Arc;
 
 mod internal;
 mod test;
+pub mod escape;
+pub mod parse;
 
 /// A dummy literal type to be used for testing or parsing literals, without
 /// depending on either `proc-macro` or `proc-macro2`. Parses the result of
@@ -37,10 +91,25 @@ mod test;
 /// ## Warning
 ///
 /// This type is easy to misuse. When given an invalid input, parsers in this
-/// crate may do the wrong thing or panic. This crate does not validate its
-/// inputs.
+/// crate will not panic, but may do the wrong thing, as they are more lenient
+/// than rustc. This crate does not fully validate its inputs.
 #[cfg(feature = "dummy")]
 pub struct DummyLiteral<T: fmt::Display>(pub T);
+#[cfg(feature = "dummy")]
+impl<'a> DummyLiteral<&'a str> {
+    /// Create a literal whose source text is exactly the given text.
+    pub fn from_source(source: &'a str) -> DummyLiteral<&'a str> {
+        DummyLiteral(source)
+    }
+
+    /// Create a literal whose source text is the given bytes. Returns `None`
+    /// if they are not valid UTF-8, as Rust source never contains such bytes,
+    /// even in byte string literals.
+    pub fn from_bytes(source: &'a [u8]) -> Option<DummyLiteral<&'a str>> {
+        str::from_utf8(source).ok().map(DummyLiteral)
+    }
+}
+
 #[cfg(feature = "dummy")]
 impl<T: fmt::Display> fmt::Display for DummyLiteral<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -53,157 +122,2058 @@ type RawInt = u64;
 #[cfg(feature = "i128")]
 type RawInt = u128;
 
-/// A type which represents an integer literal.
+/// The base an integer literal was written in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum IntBase {
+    /// Written with a `0b` prefix.
+    Binary,
+    /// Written with a `0o` prefix.
+    Octal,
+    /// Written without a prefix.
+    Decimal,
+    /// Written with a `0x` prefix.
+    Hexadecimal,
+    /// Any other radix from 3 to 36, which Rust has no prefix for. Only
+    /// produced by `parse::int_radix`. These literals do not round-trip, as
+    /// they are displayed without a prefix, and so can't be parsed again.
+    Other(Radix),
+}
+
+impl IntBase {
+    /// Get the radix of the base. This is one of 2, 8, 10, or 16, except for
+    /// `Other`.
+    pub fn radix(&self) -> u32 {
+        match *self {
+            IntBase::Binary => 2,
+            IntBase::Octal => 8,
+            IntBase::Decimal => 10,
+            IntBase::Hexadecimal => 16,
+            IntBase::Other(radix) => radix.get(),
+        }
+    }
+
+    /// Returns `None` if the radix is outside of 2 to 36.
+    fn from_radix(radix: u32) -> Option<IntBase> {
+        match radix {
+            2 => Some(IntBase::Binary),
+            8 => Some(IntBase::Octal),
+            10 => Some(IntBase::Decimal),
+            16 => Some(IntBase::Hexadecimal),
+            radix => Radix::new(radix).map(IntBase::Other),
+        }
+    }
+}
+
+/// A radix from 3 to 36 which Rust has no prefix for, such as 36. This is
+/// never 2, 8, 10 or 16, which have their own `IntBase` variants.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Radix(u32);
+
+impl Radix {
+    /// Returns `None` if the radix is outside of 2 to 36, or is one of 2, 8,
+    /// 10 or 16.
+    pub fn new(radix: u32) -> Option<Radix> {
+        match radix {
+            2 | 8 | 10 | 16 => None,
+            3..=36 => Some(Radix(radix)),
+            _ => None,
+        }
+    }
+
+    /// Get the radix as a number.
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+/// The type suffix written on an integer literal.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IntSuffix {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    Usize,
+    Isize,
+}
+
+impl IntSuffix {
+    /// Get the suffix as it is written in source, such as `"u8"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            IntSuffix::U8 => "u8",
+            IntSuffix::I8 => "i8",
+            IntSuffix::U16 => "u16",
+            IntSuffix::I16 => "i16",
+            IntSuffix::U32 => "u32",
+            IntSuffix::I32 => "i32",
+            IntSuffix::U64 => "u64",
+            IntSuffix::I64 => "i64",
+            IntSuffix::U128 => "u128",
+            IntSuffix::I128 => "i128",
+            IntSuffix::Usize => "usize",
+            IntSuffix::Isize => "isize",
+        }
+    }
+
+    fn from_suffix(s: &str) -> Option<IntSuffix> {
+        Some(match s {
+            "u8" => IntSuffix::U8,
+            "i8" => IntSuffix::I8,
+            "u16" => IntSuffix::U16,
+            "i16" => IntSuffix::I16,
+            "u32" => IntSuffix::U32,
+            "i32" => IntSuffix::I32,
+            "u64" => IntSuffix::U64,
+            "i64" => IntSuffix::I64,
+            "u128" => IntSuffix::U128,
+            "i128" => IntSuffix::I128,
+            "usize" => IntSuffix::Usize,
+            "isize" => IntSuffix::Isize,
+            _ => return None,
+        })
+    }
+}
+
+/// A type which represents an integer literal. Unlike in 0.2, this is not
+/// `Copy`, as it owns its digits, which may be too large to store as a value,
+/// and its suffix, which may be a custom suffix.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct IntLit {
     val: Option<RawInt>, // NOTE: Could be `None` if the value overflows.
-    suffix: &'static str,
+    digits: String,
+    separators: Vec<usize>,
+    suffix: Cow<'static, str>,
+    suffix_offset: Option<usize>,
+    base: IntBase,
+    negative: bool,
 }
 
 macro_rules! as_int_type {
-    ($name:ident, $t:ident) => {
-        /// Returns `None` if the value overflows, or if the suffix is wrong.
-        pub fn $name(&self) -> Option<$t> {
-            if self.suffix != "" &&
+    ($name:ident, $try_name:ident, $fits_name:ident, $t:ident) => {
+        /// Returns an error if the value overflows, or if the suffix is wrong.
+        pub fn $try_name(&self) -> Result<$t, IntConvError> {
+            if !self.suffix.is_empty() &&
                 self.suffix != stringify!($t) {
-                return None;
+                return Err(match internal::int_suffix(&self.suffix) {
+                    Some(found) => IntConvError::SuffixMismatch {
+                        expected: stringify!($t),
+                        found,
+                    },
+                    None => IntConvError::CustomSuffix {
+                        expected: stringify!($t),
+                        found: self.suffix.to_string(),
+                    },
+                });
             }
-            self.val.and_then(|v| {
-                if v > ($t::max_value() as RawInt) {
+            let val = self.val.and_then(|v| {
+                if self.negative {
+                    // NOTE: This is `0` for unsigned types, so only `-0` fits.
+                    let max = ($t::MIN as RawInt).wrapping_neg();
+                    if v > max {
+                        None
+                    } else {
+                        Some((v as $t).wrapping_neg())
+                    }
+                } else if v > ($t::MAX as RawInt) {
                     None
                 } else {
                     Some(v as $t)
                 }
-            })
+            });
+            val.ok_or_else(|| self.overflow(stringify!($t)))
+        }
+
+        /// Returns `None` if the value overflows, or if the suffix is wrong.
+        pub fn $name(&self) -> Option<$t> {
+            self.$try_name().ok()
+        }
+
+        /// Returns `true` if the value can be extracted as this type, which
+        /// requires it to fit, and the suffix to match if there is one.
+        pub fn $fits_name(&self) -> bool {
+            self.$try_name().is_ok()
         }
     }
 }
 
 impl IntLit {
-    /// Get the suffix written on the integer literal.
+    /// Create a decimal integer literal with the given value and suffix, such
+    /// as would be parsed from `5u8`. If the value does not fit in the
+    /// integer type used internally, the `as_*` methods will return `None`.
+    // NOTE: `RawInt` is `u128` when the `i128` feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(value: u128, suffix: Option<IntSuffix>) -> IntLit {
+        let digits = value.to_string();
+        IntLit {
+            val: if value > RawInt::MAX as u128 { None } else { Some(value as RawInt) },
+            separators: Vec::new(),
+            suffix: Cow::Borrowed(suffix.map_or("", |s| s.as_str())),
+            suffix_offset: suffix.map(|_| digits.len()),
+            digits,
+            base: IntBase::Decimal,
+            negative: false,
+        }
+    }
+
+    /// Get the suffix written on the integer literal. For literals parsed
+    /// with `parse::int_with_suffixes`, this may be one of the custom
+    /// suffixes.
     pub fn suffix(&self) -> &str {
         &self.suffix
     }
 
-    as_int_type!(as_u8, u8);
-    as_int_type!(as_i8, i8);
-    as_int_type!(as_u16, u16);
-    as_int_type!(as_i16, i16);
-    as_int_type!(as_u32, u32);
-    as_int_type!(as_i32, i32);
-    as_int_type!(as_u64, u64);
-    as_int_type!(as_i64, i64);
+    /// Get the suffix written on the integer literal, or `None` if it is
+    /// unsuffixed, or has a suffix which is not a Rust integer type.
+    pub fn suffix_ty(&self) -> Option<IntSuffix> {
+        IntSuffix::from_suffix(&self.suffix)
+    }
+
+    /// Get the byte offset at which the suffix begins in the text the literal
+    /// was parsed from, or `None` if it is unsuffixed.
+    pub fn suffix_offset(&self) -> Option<usize> {
+        self.suffix_offset
+    }
+
+    /// Get the digits of the integer literal, without its base prefix, digit
+    /// separators, or suffix. These are available even if the value
+    /// overflows, so `99_999u8` has the digits `99999`.
+    pub fn raw_digits(&self) -> &str {
+        &self.digits
+    }
+
+    /// Returns `true` if the digits of the integer literal were separated with
+    /// `_`, as in `1_000`.
+    pub fn has_separators(&self) -> bool {
+        !self.separators.is_empty()
+    }
+
+    /// Get the position of each `_` separator in the digits of the integer
+    /// literal, given as the number of digits which precede it. For
+    /// `1_000_000`, this is `[1, 4]`, and for `0x_FF`, it is `[0]`.
+    pub fn separators(&self) -> &[usize] {
+        &self.separators
+    }
+
+    /// Get the radix the integer literal was written in: 2, 8, 10, or 16, or
+    /// any radix up to 36 for integers parsed with `parse::int_radix`.
+    pub fn base(&self) -> u32 {
+        self.base.radix()
+    }
+
+    /// Get the base the integer literal was written in.
+    pub fn int_base(&self) -> IntBase {
+        self.base
+    }
+
+    /// Returns `true` if the integer was written with a leading `-`. This is
+    /// only possible for integers parsed with `parse::signed_int`, as
+    /// `Literal` tokens never contain a sign.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Converts the value to the nearest `f64`, whatever the suffix is, so
+    /// integers above `2^53` may lose precision. Decimal integers are rounded
+    /// correctly however large they are, but integers in other bases return
+    /// `None` if they overflow the `u128` (or `u64` without the `i128`
+    /// feature) used to store the value.
+    pub fn to_float_lossy(&self) -> Option<f64> {
+        internal::int_f64(self, false)
+    }
+
+    /// Like `to_float_lossy`, but returns `None` unless the value is exactly
+    /// representable as an `f64`, so `2^53 + 1` is `None`, while `2^60` is
+    /// not.
+    pub fn to_float_exact(&self) -> Option<f64> {
+        internal::int_f64(self, true)
+    }
+
+    /// Returns `true` if the integer has a Rust integer suffix, and its value
+    /// is out of range for that type, as in `256u8`. rustc rejects these
+    /// literals with "literal out of range". Without the `i128` feature, this
+    /// is always `false` for the `i128` and `u128` suffixes.
+    pub fn declared_overflow(&self) -> bool {
+        let ty = match self.suffix_ty() {
+            Some(ty) => ty,
+            None => return false,
+        };
+        match ty {
+            IntSuffix::U8 => !self.fits_u8(),
+            IntSuffix::I8 => !self.fits_i8(),
+            IntSuffix::U16 => !self.fits_u16(),
+            IntSuffix::I16 => !self.fits_i16(),
+            IntSuffix::U32 => !self.fits_u32(),
+            IntSuffix::I32 => !self.fits_i32(),
+            IntSuffix::U64 => !self.fits_u64(),
+            IntSuffix::I64 => !self.fits_i64(),
+            #[cfg(feature = "i128")]
+            IntSuffix::U128 => !self.fits_u128(),
+            #[cfg(feature = "i128")]
+            IntSuffix::I128 => !self.fits_i128(),
+            #[cfg(not(feature = "i128"))]
+            IntSuffix::U128 | IntSuffix::I128 => false,
+            IntSuffix::Usize => !self.fits_usize(),
+            IntSuffix::Isize => !self.fits_isize(),
+        }
+    }
+
+    /// Returns the value as a `BigInt`, whatever the suffix is. Unlike the
+    /// other methods, this works however large the value is, as it parses the
+    /// digits of the literal, rather than its value.
+    #[cfg(feature = "num-bigint")]
+    pub fn as_bigint(&self) -> Option<num_bigint::BigInt> {
+        let val = num_bigint::BigInt::parse_bytes(self.digits.as_bytes(), self.base.radix())?;
+        Some(if self.negative { -val } else { val })
+    }
+
+    /// Returns the value as an `i128` if it fits, whatever the suffix is.
+    /// Unlike `as_i128`, the suffix need not match, so `5u8` is `Some(5)`.
+    #[cfg(feature = "i128")]
+    pub fn as_i128_any(&self) -> Option<i128> {
+        let v = self.val?;
+        if self.negative {
+            if v > (i128::MIN as u128).wrapping_neg() {
+                None
+            } else {
+                Some((v as i128).wrapping_neg())
+            }
+        } else if v > i128::MAX as u128 {
+            None
+        } else {
+            Some(v as i128)
+        }
+    }
+
+    /// Returns the value as a `u128` if it fits, whatever the suffix is.
+    /// Unlike `as_u128`, the suffix need not match, so `5i8` is `Some(5)`.
+    #[cfg(feature = "i128")]
+    pub fn as_u128_any(&self) -> Option<u128> {
+        let v = self.val?;
+        // NOTE: Only `-0` fits in an unsigned type.
+        if self.negative && v != 0 {
+            None
+        } else {
+            Some(v)
+        }
+    }
+
+    /// Returns the value if it is in the range of the given type, chosen at
+    /// runtime, or `None` if it is out of range, or the literal has a suffix
+    /// for a different type. An unsuffixed literal may be used as any type.
+    ///
+    /// Negative values only fit in signed types, whose range is from `MIN` to
+    /// `MAX`, while unsigned types range from `0` to `MAX`. As the result is an
+    /// `i128`, a `u128` value above `i128::MAX` is `None`, even though it is in
+    /// range for `u128`. This does not need the `i128` feature, but without it
+    /// values above `u64::MAX` overflow, so are always `None`.
+    pub fn as_int_of(&self, ty: IntSuffix) -> Option<i128> {
+        if !self.suffix.is_empty() && self.suffix != ty.as_str() {
+            return None;
+        }
+        let (min, max) = match ty {
+            IntSuffix::U8 => (0, u8::MAX as i128),
+            IntSuffix::I8 => (i8::MIN as i128, i8::MAX as i128),
+            IntSuffix::U16 => (0, u16::MAX as i128),
+            IntSuffix::I16 => (i16::MIN as i128, i16::MAX as i128),
+            IntSuffix::U32 => (0, u32::MAX as i128),
+            IntSuffix::I32 => (i32::MIN as i128, i32::MAX as i128),
+            IntSuffix::U64 => (0, u64::MAX as i128),
+            IntSuffix::I64 => (i64::MIN as i128, i64::MAX as i128),
+            IntSuffix::U128 => (0, i128::MAX),
+            IntSuffix::I128 => (i128::MIN, i128::MAX),
+            IntSuffix::Usize => (0, usize::MAX as i128),
+            IntSuffix::Isize => (isize::MIN as i128, isize::MAX as i128),
+        };
+        // NOTE: `RawInt` is `u128` when the `i128` feature is enabled.
+        #[allow(clippy::useless_conversion)]
+        let v = u128::from(self.val?);
+        let val = if self.negative {
+            if v > i128::MIN.unsigned_abs() {
+                return None;
+            }
+            (v as i128).wrapping_neg()
+        } else {
+            i128::try_from(v).ok()?
+        };
+        Some(val).filter(|v| (min..=max).contains(v))
+    }
+
+    fn overflow(&self, ty: &'static str) -> IntConvError {
+        let mut value = self.to_string();
+        let len = value.len() - self.suffix.len();
+        value.truncate(len);
+        IntConvError::Overflow { value, ty }
+    }
+
+    as_int_type!(as_u8, try_as_u8, fits_u8, u8);
+    as_int_type!(as_i8, try_as_i8, fits_i8, i8);
+    as_int_type!(as_u16, try_as_u16, fits_u16, u16);
+    as_int_type!(as_i16, try_as_i16, fits_i16, i16);
+    as_int_type!(as_u32, try_as_u32, fits_u32, u32);
+    as_int_type!(as_i32, try_as_i32, fits_i32, i32);
+    as_int_type!(as_u64, try_as_u64, fits_u64, u64);
+    as_int_type!(as_i64, try_as_i64, fits_i64, i64);
+    as_int_type!(as_usize, try_as_usize, fits_usize, usize);
+    as_int_type!(as_isize, try_as_isize, fits_isize, isize);
     #[cfg(feature = "i128")]
-    as_int_type!(as_u128, u128);
+    as_int_type!(as_u128, try_as_u128, fits_u128, u128);
     #[cfg(feature = "i128")]
-    as_int_type!(as_i128, i128);
+    as_int_type!(as_i128, try_as_i128, fits_i128, i128);
 }
 
-/// A type which represents a floating point value.
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct FloatLit {
-    val: f64,
-    suffix: &'static str,
+macro_rules! int_try_from {
+    ($try_name:ident, $t:ident) => {
+        /// Converts the literal as `IntLit::try_as_*` does.
+        impl TryFrom<IntLit> for $t {
+            type Error = IntConvError;
+            fn try_from(lit: IntLit) -> Result<$t, IntConvError> {
+                lit.$try_name()
+            }
+        }
+
+        impl<'a> TryFrom<&'a IntLit> for $t {
+            type Error = IntConvError;
+            fn try_from(lit: &'a IntLit) -> Result<$t, IntConvError> {
+                lit.$try_name()
+            }
+        }
+    }
 }
 
-macro_rules! as_float_type {
+int_try_from!(try_as_u8, u8);
+int_try_from!(try_as_i8, i8);
+int_try_from!(try_as_u16, u16);
+int_try_from!(try_as_i16, i16);
+int_try_from!(try_as_u32, u32);
+int_try_from!(try_as_i32, i32);
+int_try_from!(try_as_u64, u64);
+int_try_from!(try_as_i64, i64);
+int_try_from!(try_as_usize, usize);
+int_try_from!(try_as_isize, isize);
+#[cfg(feature = "i128")]
+int_try_from!(try_as_u128, u128);
+#[cfg(feature = "i128")]
+int_try_from!(try_as_i128, i128);
+
+macro_rules! int_eq {
     ($name:ident, $t:ident) => {
-        /// Returns `None` if the suffix does not match the requested type.
-        pub fn $name(&self) -> Option<$t> {
-            if self.suffix != "" && self.suffix != stringify!($t) {
-                return None
-            } else {
-                Some(self.val as $t)
+        /// Compares the literal as `IntLit::as_*` extracts it, so the literal is
+        /// equal only when its value matches and its suffix is absent or names
+        /// this type. `5` and `5u8` are equal to `5u8`, but `5u64` is not.
+        impl PartialEq<$t> for IntLit {
+            fn eq(&self, other: &$t) -> bool {
+                self.$name() == Some(*other)
             }
         }
     }
 }
 
-impl FloatLit {
-    /// Get the suffix for the float.
-    pub fn suffix(&self) -> &str {
-        &self.suffix
+int_eq!(as_u8, u8);
+int_eq!(as_i8, i8);
+int_eq!(as_u16, u16);
+int_eq!(as_i16, i16);
+int_eq!(as_u32, u32);
+int_eq!(as_i32, i32);
+int_eq!(as_u64, u64);
+int_eq!(as_i64, i64);
+int_eq!(as_usize, usize);
+int_eq!(as_isize, isize);
+#[cfg(feature = "i128")]
+int_eq!(as_u128, u128);
+#[cfg(feature = "i128")]
+int_eq!(as_i128, i128);
+
+/// Renders the integer as a literal in the base it was written in, followed by
+/// its suffix. The output is not necessarily identical to the original text,
+/// as digit separators are removed. Negative integers are rendered with a
+/// leading `-`, which is not part of the literal token.
+impl fmt::Display for IntLit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        let prefix = match self.base {
+            IntBase::Binary => "0b",
+            IntBase::Octal => "0o",
+            IntBase::Decimal => "",
+            IntBase::Hexadecimal => "0x",
+            IntBase::Other(_) => "",
+        };
+        write!(f, "{}{}{}", prefix, self.digits, self.suffix)
+    }
+}
+
+/// An error describing why an `IntLit` could not be converted to an integer
+/// type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum IntConvError {
+    /// The literal has a suffix for a different type than the one requested.
+    SuffixMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The literal has a custom suffix, accepted by `parse::int_with_suffixes`,
+    /// which is not the suffix of any integer type.
+    CustomSuffix {
+        expected: &'static str,
+        found: String,
+    },
+    /// The literal's value does not fit in the requested type. The value is
+    /// rendered as it was written, without its suffix.
+    Overflow {
+        value: String,
+        ty: &'static str,
+    },
+}
+
+impl fmt::Display for IntConvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IntConvError::SuffixMismatch { expected, found } => {
+                write!(f, "suffix `{}` on a value used as `{}`", found, expected)
+            }
+            IntConvError::CustomSuffix { expected, ref found } => {
+                write!(f, "custom suffix `{}` on a value used as `{}`", found, expected)
+            }
+            IntConvError::Overflow { ref value, ty } => {
+                write!(f, "value `{}` is out of range for `{}`", value, ty)
+            }
+        }
     }
+}
 
-    as_float_type!(as_f32, f32);
-    as_float_type!(as_f64, f64);
+#[cfg(feature = "std")]
+impl error::Error for IntConvError {}
+
+/// Orders integers by their numeric value, with values which overflowed
+/// comparing greater than any representable value. Integers with equal values
+/// are ordered by their suffix, then by how they were written.
+impl Ord for IntLit {
+    fn cmp(&self, other: &IntLit) -> Ordering {
+        // NOTE: An overflowed value is larger in magnitude than any other.
+        fn magnitude(i: &IntLit) -> (bool, RawInt) {
+            i.val.map_or((true, 0), |v| (false, v))
+        }
+        let value = match (self.negative, other.negative) {
+            (false, false) => magnitude(self).cmp(&magnitude(other)),
+            (true, true) => magnitude(other).cmp(&magnitude(self)),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        };
+        value
+            .then_with(|| self.suffix.cmp(&other.suffix))
+            .then_with(|| self.base.cmp(&other.base))
+            .then_with(|| self.digits.cmp(&other.digits))
+    }
 }
 
-pub trait LiteralExt {
-    /// If the `Literal` is an integer literal, returns its value.
-    fn parse_int(&self) -> Option<IntLit>;
+impl PartialOrd for IntLit {
+    fn partial_cmp(&self, other: &IntLit) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-    /// If the `Literal` is a floating point literal, returns its value.
-    fn parse_float(&self) -> Option<FloatLit>;
+/// The positions of the suffix and digit separators in the source text are not
+/// part of the literal's value, so they are ignored.
+impl PartialEq for IntLit {
+    fn eq(&self, other: &IntLit) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
 
-    /// If the `Literal` is a string literal, returns it's value.
-    fn parse_string(&self) -> Option<String>;
+impl Eq for IntLit {}
 
-    /// If the `Literal` is a char literal, returns it's value.
-    fn parse_char(&self) -> Option<char>;
+/// Hashes the same parts of the literal which are compared by `Eq`, so the
+/// positions of the suffix and digit separators are ignored.
+impl Hash for IntLit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.val.hash(state);
+        self.negative.hash(state);
+        self.suffix.hash(state);
+        self.base.hash(state);
+        self.digits.hash(state);
+    }
+}
 
-    /// If the `Literal` is a byte string literal, returns it's value.
-    fn parse_bytes(&self) -> Option<Vec<u8>>;
+/// Parses the text of an integer literal, such as `42u8`.
+impl FromStr for IntLit {
+    type Err = LiteralError;
 
-    /// If the `Literal` is a byte literal, returns it's value.
-    fn parse_byte(&self) -> Option<u8>;
+    fn from_str(s: &str) -> Result<IntLit, LiteralError> {
+        internal::int_lit(s)
+    }
+}
 
-    /// If the `Literal` is an inner doc comment (`//!` or `/*!`), returns a
-    /// string with the text of the comment.
-    fn parse_inner_doc(&self) -> Option<String>;
+/// The type suffix written on a floating point literal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FloatSuffix {
+    F32,
+    F64,
+}
 
-    /// If the `Literal` is an outer doc comment (`///` or `/**`), returns a
-    /// string with the text of the comment.
-    fn parse_outer_doc(&self) -> Option<String>;
+impl FloatSuffix {
+    /// Get the suffix as it is written in source, such as `"f32"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            FloatSuffix::F32 => "f32",
+            FloatSuffix::F64 => "f64",
+        }
+    }
+
+    fn from_suffix(s: &str) -> Option<FloatSuffix> {
+        Some(match s {
+            "f32" => FloatSuffix::F32,
+            "f64" => FloatSuffix::F64,
+            _ => return None,
+        })
+    }
 }
 
-macro_rules! impl_literal {
-    () => {
-        fn parse_int(&self) -> Option<IntLit> {
-            $crate::internal::int_lit(&self.to_string())
+/// A type which represents a floating point value.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct FloatLit {
+    val: f64,
+    suffix: &'static str,
+    suffix_offset: Option<usize>,
+    repr: FloatRepr,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    long: Option<LongDigits>,
+}
+
+/// The results of `FloatLit::is_exact` and `FloatLit::decompose`, computed
+/// when the literal is parsed, for digits which are too long to be kept in a
+/// `FloatRepr`.
+#[derive(Debug, Copy, Clone)]
+struct LongDigits {
+    exact: bool,
+    decomposed: Option<(i128, i32)>,
+}
+
+/// The longest digits, in bytes, which a `FloatRepr` can hold.
+const FLOAT_REPR_LEN: usize = 64;
+
+/// How the digits of a floating point literal were written, such that the
+/// literal can be reproduced exactly. For `1_0.5E+23f32`, the mantissa is
+/// `1_0.5`, the exponent marker is `E`, the exponent sign is `+`, and the
+/// exponent digits are `23`.
+///
+/// The digits are stored inline, so that `FloatLit` can be `Copy`. Digits
+/// longer than 64 bytes can't be kept, so for those literals, this is the
+/// shortest form which round-trips, as for a literal created with
+/// `FloatLit::new`.
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde",
+           serde(into = "internal::FloatReprData", try_from = "internal::FloatReprData"))]
+pub struct FloatRepr {
+    // NOTE: The bytes after `len` are always zero, so they can be compared.
+    text: [u8; FLOAT_REPR_LEN],
+    len: u8,
+    mantissa_len: u8,
+}
+
+impl FloatRepr {
+    /// Get the digits, without the suffix, or `None` if they are too long.
+    fn new(text: &str) -> Option<FloatRepr> {
+        if text.len() > FLOAT_REPR_LEN {
+            return None;
         }
+        let mut repr = FloatRepr {
+            text: [0; FLOAT_REPR_LEN],
+            len: text.len() as u8,
+            mantissa_len: text.find(['e', 'E']).unwrap_or(text.len()) as u8,
+        };
+        repr.text[..text.len()].copy_from_slice(text.as_bytes());
+        Some(repr)
+    }
 
-        fn parse_float(&self) -> Option<FloatLit> {
-            $crate::internal::float_lit(self.to_string())
+    /// Get all of the digits, as they were written.
+    fn as_str(&self) -> &str {
+        // NOTE: The bytes were copied from a `str`, so this cannot fail.
+        str::from_utf8(&self.text[..self.len as usize]).unwrap_or("")
+    }
+
+    /// Get the exponent marker, sign and digits.
+    fn exp(&self) -> &str {
+        &self.as_str()[self.mantissa_len as usize..]
+    }
+
+    /// Get the digits before the exponent, including the `.` and any `_`
+    /// separators, as they were written.
+    pub fn mantissa(&self) -> &str {
+        &self.as_str()[..self.mantissa_len as usize]
+    }
+
+    /// Returns `true` if the mantissa contains a `.`.
+    pub fn has_dot(&self) -> bool {
+        self.mantissa().contains('.')
+    }
+
+    /// Get the character which introduced the exponent, either `e` or `E`, or
+    /// `None` if there is no exponent.
+    pub fn exp_marker(&self) -> Option<char> {
+        self.exp().chars().next()
+    }
+
+    /// Get the sign written before the exponent digits, either `+` or `-`, or
+    /// `None` if there was no sign.
+    pub fn exp_sign(&self) -> Option<char> {
+        self.exp().chars().nth(1).filter(|&c| c == '+' || c == '-')
+    }
+
+    /// Get the digits of the exponent, including any `_` separators, as they
+    /// were written. This is empty if there is no exponent.
+    pub fn exp_digits(&self) -> &str {
+        let exp = self.exp();
+        let skip = self.exp_marker().map_or(0, |_| 1) + self.exp_sign().map_or(0, |_| 1);
+        &exp[skip..]
+    }
+}
+
+impl fmt::Debug for FloatRepr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FloatRepr")
+            .field("mantissa", &self.mantissa())
+            .field("has_dot", &self.has_dot())
+            .field("exp_marker", &self.exp_marker())
+            .field("exp_sign", &self.exp_sign())
+            .field("exp_digits", &self.exp_digits())
+            .finish()
+    }
+}
+
+/// Renders the digits exactly as they were written, without the suffix.
+impl fmt::Display for FloatRepr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+macro_rules! as_float_type {
+    ($name:ident, $try_name:ident, $t:ident) => {
+        /// Returns `None` if the suffix does not match the requested type.
+        pub fn $name(&self) -> Option<$t> {
+            if !self.suffix.is_empty() && self.suffix != stringify!($t) {
+                return None
+            } else {
+                Some(self.val as $t)
+            }
         }
 
-        fn parse_string(&self) -> Option<String> {
-            $crate::internal::str_lit(&self.to_string())
+        /// Returns an error if the suffix does not match the requested type,
+        /// or if the value is too large to be represented by it, and would
+        /// become infinite.
+        pub fn $try_name(&self) -> Result<$t, FloatConvError> {
+            if !self.suffix.is_empty() && self.suffix != stringify!($t) {
+                return Err(FloatConvError::SuffixMismatch {
+                    expected: stringify!($t),
+                    found: self.suffix,
+                });
+            }
+            let val = self.val as $t;
+            if val.is_infinite() {
+                return Err(FloatConvError::Overflow { ty: stringify!($t) });
+            }
+            Ok(val)
         }
+    }
+}
 
-        fn parse_char(&self) -> Option<char> {
-            $crate::internal::char_lit(&self.to_string())
+impl FloatLit {
+    /// Create a floating point literal with the given value and suffix, such
+    /// as would be parsed from `5.0f32`.
+    pub fn new(value: f64, suffix: Option<FloatSuffix>) -> FloatLit {
+        let mut lit = FloatLit {
+            val: value,
+            suffix: suffix.map_or("", |s| s.as_str()),
+            suffix_offset: None,
+            repr: internal::float_repr_of(value),
+            long: None,
+        };
+        if !lit.suffix.is_empty() {
+            lit.suffix_offset = Some(lit.to_string().len() - lit.suffix.len());
         }
+        lit
+    }
 
-        fn parse_bytes(&self) -> Option<Vec<u8>> {
-            $crate::internal::byte_str_lit(&self.to_string())
+    /// Get the suffix for the float.
+    pub fn suffix(&self) -> &str {
+        self.suffix
+    }
+
+    /// Get the byte offset at which the suffix begins in the text the literal
+    /// was parsed from, or `None` if it is unsuffixed.
+    pub fn suffix_offset(&self) -> Option<usize> {
+        self.suffix_offset
+    }
+
+    /// Get the suffix for the float, or `None` if it is unsuffixed.
+    pub fn suffix_ty(&self) -> Option<FloatSuffix> {
+        FloatSuffix::from_suffix(self.suffix)
+    }
+
+    /// Get how the digits of the literal were written. For a literal created
+    /// with `new`, or with digits which are too long to keep, this is the
+    /// shortest form which round-trips.
+    pub fn repr(&self) -> &FloatRepr {
+        &self.repr
+    }
+
+    /// Converts the value, at the type given by the suffix, to an integer,
+    /// returning `None` if it has a fractional part, or is out of range for an
+    /// `i128`. The value is used rather than the written digits, so `1e30` is
+    /// the integer nearest to `1e30` which is an `f64`.
+    pub fn to_int_exact(&self) -> Option<i128> {
+        let val = self.suffix_val();
+        // NOTE: `i128::MAX as f64` rounds up to `2^127`, which is out of range.
+        // `f64::fract` needs `std`, so check the truncating cast instead.
+        if !(val >= i128::MIN as f64 && val < i128::MAX as f64) || val as i128 as f64 != val {
+            return None;
         }
+        Some(val as i128)
+    }
 
-        fn parse_byte(&self) -> Option<u8> {
-            $crate::internal::byte_lit(&self.to_string())
+    /// Decompose the decimal number written in the literal into an integer
+    /// mantissa and a power of ten, such that `mantissa * 10^exp` is the
+    /// number, without going through the `f64` value. For `1.25e3`, this is
+    /// `(125, 1)`. The number is as it was written, so it may not be exactly
+    /// equal to the value (see `is_exact`). Returns `None` if the digits do
+    /// not fit in an `i128`, or the exponent does not fit in an `i32`.
+    pub fn decompose(&self) -> Option<(i128, i32)> {
+        match self.long {
+            Some(long) => long.decomposed,
+            None => internal::float_decompose(self.repr.as_str()),
         }
+    }
 
-        fn parse_inner_doc(&self) -> Option<String> {
-            $crate::internal::inner_doc(self.to_string())
+    /// Returns `true` if the value, at the type given by the suffix, is
+    /// exactly equal to the decimal number written in the literal. This is
+    /// `false` for `0.1`, which has no exact binary representation, and for
+    /// `16777217f32`, which is too precise for an `f32`, but `true` for
+    /// `0.5` and `1e10`. For a literal created with `new`, this compares
+    /// against the shortest form which round-trips.
+    pub fn is_exact(&self) -> bool {
+        match self.long {
+            Some(long) => long.exact,
+            None => internal::float_is_exact(self.repr.as_str(), self.suffix_val()),
         }
+    }
 
-        fn parse_outer_doc(&self) -> Option<String> {
-            $crate::internal::outer_doc(self.to_string())
+    /// Get the value at the type given by the suffix, widened to an `f64`.
+    fn suffix_val(&self) -> f64 {
+        if self.suffix == "f32" {
+            self.val as f32 as f64
+        } else {
+            self.val
         }
     }
-}
 
-#[cfg(feature = "dummy")]
-impl<T: fmt::Display> LiteralExt for DummyLiteral<T> {
-    impl_literal!();
+    /// Returns `false` if the value was too large to be represented by an
+    /// `f64`, and became infinite. Rust has no literals for infinity, so this
+    /// is always the result of overflow.
+    pub fn is_finite(&self) -> bool {
+        self.val.is_finite()
+    }
+
+    as_float_type!(as_f32, try_as_f32, f32);
+    as_float_type!(as_f64, try_as_f64, f64);
 }
 
-#[cfg(feature = "proc-macro")]
-impl LiteralExt for proc_macro::Literal {
-    impl_literal!();
+/// An error describing why a `FloatLit` could not be converted to a floating
+/// point type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FloatConvError {
+    /// The literal has a suffix for a different type than the one requested.
+    SuffixMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The literal's value is too large for the requested type.
+    Overflow {
+        ty: &'static str,
+    },
+}
+
+impl fmt::Display for FloatConvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FloatConvError::SuffixMismatch { expected, found } => {
+                write!(f, "suffix `{}` on a value used as `{}`", found, expected)
+            }
+            FloatConvError::Overflow { ty } => {
+                write!(f, "value is out of range for `{}`", ty)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for FloatConvError {}
+
+/// Renders the float as a literal followed by its suffix. The value is written
+/// in the shortest form which round-trips, which will always contain a `.` or
+/// an exponent, so the output is not necessarily identical to the original
+/// text.
+impl fmt::Display for FloatLit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{}", self.val, self.suffix)
+    }
+}
+
+/// How the digits were written and the position of the suffix in the source
+/// text are not part of the literal's value, so they are ignored.
+impl PartialEq for FloatLit {
+    fn eq(&self, other: &FloatLit) -> bool {
+        self.val == other.val && self.suffix == other.suffix
+    }
+}
+
+/// Hashes the bits of the value, from `f64::to_bits`, along with the suffix.
+/// Zero is always hashed as positive zero, as `0.0 == -0.0`, so this agrees
+/// with `PartialEq` for every value except `NaN`, which is never equal to
+/// itself but always hashes the same. Literals are never `NaN`, but a
+/// `FloatLit` may be created with one, so `FloatLit` does not implement `Eq`.
+/// To use it as the key of a `HashMap`, wrap it in a type which implements
+/// `Eq`, having checked that the value is not `NaN`.
+impl Hash for FloatLit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let val = if self.val == 0.0 { 0.0f64 } else { self.val };
+        val.to_bits().hash(state);
+        self.suffix.hash(state);
+    }
+}
+
+/// Parses the text of a floating point literal, such as `3.14f32`.
+impl FromStr for FloatLit {
+    type Err = LiteralError;
+
+    fn from_str(s: &str) -> Result<FloatLit, LiteralError> {
+        internal::float_lit(s)
+    }
+}
+
+/// A type which represents a string literal, along with how it was written.
+/// It dereferences to the decoded value of the string, so can be used as a
+/// `&str`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StrLit {
+    val: String,
+    raw: bool,
+    hash_count: usize,
+    suffix: String,
+}
+
+impl StrLit {
+    /// Get the decoded value of the string.
+    pub fn value(&self) -> &str {
+        &self.val
+    }
+
+    /// Consume the literal, returning the decoded value of the string.
+    pub fn into_value(self) -> String {
+        self.val
+    }
+
+    /// Returns `true` if the string was written as a raw string, such as
+    /// `r"..."` or `r#"..."#`.
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Get the number of `#` characters which delimit a raw string. This is
+    /// always `0` for a string which isn't raw.
+    pub fn hash_count(&self) -> usize {
+        self.hash_count
+    }
+
+    /// Get the suffix written after the closing quote, such as `bar` for
+    /// `"foo"bar`, or `""` if there is none. Rust reserves these suffixes, so
+    /// they are only found in tokens which are consumed by macros.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+}
+
+impl Deref for StrLit {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.val
+    }
+}
+
+impl AsRef<str> for StrLit {
+    fn as_ref(&self) -> &str {
+        &self.val
+    }
+}
+
+impl PartialEq<str> for StrLit {
+    fn eq(&self, other: &str) -> bool {
+        self.val == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for StrLit {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.val == *other
+    }
+}
+
+impl PartialEq<String> for StrLit {
+    fn eq(&self, other: &String) -> bool {
+        self.val == *other
+    }
+}
+
+/// How the value of a char or byte literal was written.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EscapeKind {
+    /// Written without an escape, such as `'A'`.
+    None,
+    /// Written as a backslash followed by the char, such as `'\''` or `'\\'`.
+    Ascii,
+    /// Written as a hex escape, such as `'\x41'`.
+    Hex,
+    /// Written as a unicode escape, such as `'\u{41}'`.
+    Unicode,
+    /// Written as a named escape: `'\n'`, `'\r'`, `'\t'` or `'\0'`.
+    Named,
+}
+
+/// A type which represents a char literal, along with how it was written.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CharLit {
+    val: char,
+    escape: EscapeKind,
+}
+
+impl CharLit {
+    /// Get the decoded value of the char.
+    pub fn value(&self) -> char {
+        self.val
+    }
+
+    /// Get the kind of escape the char was written with.
+    pub fn escape(&self) -> EscapeKind {
+        self.escape
+    }
+}
+
+/// A type which represents a byte literal, along with how it was written.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ByteLit {
+    val: u8,
+    escape: EscapeKind,
+}
+
+impl ByteLit {
+    /// Get the decoded value of the byte.
+    pub fn value(&self) -> u8 {
+        self.val
+    }
+
+    /// Get the kind of escape the byte was written with. This is never
+    /// `EscapeKind::Unicode`, as unicode escapes are not allowed in byte
+    /// literals.
+    pub fn escape(&self) -> EscapeKind {
+        self.escape
+    }
+}
+
+/// An iterator over the bytes of a byte string literal, which decodes its
+/// escapes as the bytes are requested, rather than collecting them.
+#[derive(Debug, Clone)]
+pub struct ByteStrIter<'a> {
+    text: Cow<'a, str>,
+    pos: usize,
+    end: usize,
+    raw: bool,
+}
+
+impl<'a> Iterator for ByteStrIter<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        internal::byte_str_iter_next(self)
+    }
+}
+
+/// An iterator over the chars of a string literal, which decodes its escapes
+/// as the chars are requested, rather than collecting them.
+#[derive(Debug, Clone)]
+pub struct StrIter<'a> {
+    text: Cow<'a, str>,
+    pos: usize,
+    end: usize,
+    raw: bool,
+}
+
+impl<'a> Iterator for StrIter<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        internal::str_iter_next(self)
+    }
+}
+
+/// An error describing why a literal could not be interpreted. Offsets are
+/// byte offsets into the literal's text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LiteralError {
+    /// The literal is not of the requested kind.
+    NotThisKind,
+    /// An escape sequence was unrecognized or malformed.
+    InvalidEscape { offset: usize },
+    /// A `\u` escape named a value which is not a unicode scalar value, or a C
+    /// string contained a NUL.
+    InvalidCodePoint { offset: usize },
+    /// A carriage return was not followed by a line feed.
+    BareCarriageReturn { offset: usize },
+    /// The literal ended before it was terminated.
+    UnterminatedLiteral { offset: usize },
+    /// A digit was out of range for the base of an integer literal.
+    InvalidDigit { offset: usize },
+    /// Digits were required, but none were found.
+    MissingDigits { offset: usize },
+    /// A char or byte literal contained more than one character, or a byte
+    /// literal contained a non-ASCII character.
+    MultipleCharacters { offset: usize },
+    /// The literal had unexpected trailing characters.
+    InvalidSuffix { offset: usize },
+    /// An integer literal's value is out of range for its suffix, as in
+    /// `256u8`, which rustc rejects.
+    Overflow { offset: usize },
+}
+
+impl LiteralError {
+    /// Get the byte offset at which the problem was found, if the error has
+    /// one.
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            LiteralError::NotThisKind => None,
+            LiteralError::InvalidEscape { offset } |
+            LiteralError::InvalidCodePoint { offset } |
+            LiteralError::BareCarriageReturn { offset } |
+            LiteralError::UnterminatedLiteral { offset } |
+            LiteralError::InvalidDigit { offset } |
+            LiteralError::MissingDigits { offset } |
+            LiteralError::MultipleCharacters { offset } |
+            LiteralError::InvalidSuffix { offset } |
+            LiteralError::Overflow { offset } => Some(offset),
+        }
+    }
+}
+
+/// Describes the problem, followed by the byte offset at which it was found,
+/// such as `invalid escape at offset 3`. Unsuffixed integers which are too
+/// large to store are not parse errors, and are reported by `IntConvError`
+/// when they are converted.
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match *self {
+            LiteralError::NotThisKind => "not a literal of the requested kind",
+            LiteralError::InvalidEscape { .. } => "invalid escape",
+            LiteralError::InvalidCodePoint { .. } => "invalid unicode code point",
+            LiteralError::BareCarriageReturn { .. } => "bare CR not allowed",
+            LiteralError::UnterminatedLiteral { .. } => "unterminated literal",
+            LiteralError::InvalidDigit { .. } => "invalid digit for the base",
+            LiteralError::MissingDigits { .. } => "missing digits",
+            LiteralError::MultipleCharacters { .. } => "literal contains more than one character",
+            LiteralError::InvalidSuffix { .. } => "invalid suffix",
+            LiteralError::Overflow { .. } => "integer out of range for its suffix",
+        };
+        f.write_str(message)?;
+        match self.offset() {
+            Some(offset) => write!(f, " at offset {}", offset),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for LiteralError {}
+
+/// The kind of a literal, as determined by `LiteralExt::kind`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LiteralKind {
+    /// An integer literal, such as `5` or `0xFFu8`.
+    Int,
+    /// A floating point literal, such as `5.0` or `1e10f32`.
+    Float,
+    /// A string literal, such as `"a"` or `r#"a"#`.
+    Str,
+    /// A byte string literal, such as `b"a"` or `br#"a"#`.
+    ByteStr,
+    /// A C string literal, such as `c"a"` or `cr#"a"#`.
+    CStr,
+    /// A char literal, such as `'a'`.
+    Char,
+    /// A byte literal, such as `b'a'`.
+    Byte,
+    /// An inner doc comment (`//!` or `/*!`).
+    InnerDoc,
+    /// An outer doc comment (`///` or `/**`).
+    OuterDoc,
+    /// Not a recognized kind of literal.
+    Unknown,
+}
+
+/// The decoded value of a literal, as returned by `LiteralExt::value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LitValue {
+    /// An integer literal.
+    Int(IntLit),
+    /// A floating point literal.
+    Float(FloatLit),
+    /// A string literal.
+    Str(String),
+    /// A byte string literal.
+    ByteStr(Vec<u8>),
+    /// A C string literal, including its trailing NUL.
+    CStr(Vec<u8>),
+    /// A char literal.
+    Char(char),
+    /// A byte literal.
+    Byte(u8),
+    /// An inner doc comment.
+    InnerDoc(String),
+    /// An outer doc comment.
+    OuterDoc(String),
+}
+
+/// Classifies the text with `parse::kind`, and decodes it with the parser for
+/// that kind, returning that parser's error if it is malformed. Text which is
+/// not a literal of any kind is `LiteralError::NotThisKind`, and integers which
+/// are out of range for their suffix, such as `256u8`, are
+/// `LiteralError::Overflow`, as they are for `LiteralExt::value`.
+impl<'a> TryFrom<&'a str> for LitValue {
+    type Error = LiteralError;
+
+    fn try_from(s: &'a str) -> Result<LitValue, LiteralError> {
+        internal::lit_value_of_kind(s)
+    }
+}
+
+/// A problem found while decoding a literal with `LiteralExt::parse_lenient`,
+/// such as an unknown escape sequence.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LiteralDiagnostic {
+    range: Range<usize>,
+    message: String,
+}
+
+impl LiteralDiagnostic {
+    /// Get the range of bytes in the literal's text which the problem covers.
+    /// This is empty for problems at a single position, such as a missing
+    /// closing quote.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get a description of the problem, such as ``unknown escape `\q` ``.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A decoded literal, along with the text it was parsed from, as returned by
+/// `LiteralExt::parse_full`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedLit {
+    text: String,
+    value: LitValue,
+}
+
+impl ParsedLit {
+    /// Get the text of the literal, as it was written in source.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the decoded value of the literal.
+    pub fn value(&self) -> &LitValue {
+        &self.value
+    }
+
+    /// Consume the literal, returning its decoded value.
+    pub fn into_value(self) -> LitValue {
+        self.value
+    }
+}
+
+pub trait LiteralExt {
+    /// If the `Literal` is an integer literal, returns its value.
+    fn parse_int(&self) -> Option<IntLit>;
+
+    /// If the `Literal` is a floating point literal, returns its value.
+    fn parse_float(&self) -> Option<FloatLit>;
+
+    /// Like `parse_float`, but also accepts an unsuffixed decimal integer,
+    /// such as `1`, as a float. Some backends render floats with integral
+    /// values this way, such as `proc_macro::Literal::f64_unsuffixed(1.0)`,
+    /// making them indistinguishable from integers, so this should only be
+    /// used when the token is known to be a float.
+    fn parse_float_forgiving(&self) -> Option<FloatLit>;
+
+    /// If the `Literal` is an integer or float literal, returns it's value as
+    /// an `f64`, whatever it's suffix is, so `5`, `5u8` and `5.0` are all
+    /// `5.0`. Integers which are too large to be represented exactly are
+    /// rounded, so this loses precision for values above `2^53`.
+    fn parse_number_f64(&self) -> Option<f64> {
+        internal::number_f64(self.parse_int(), || self.parse_float())
+    }
+
+    /// Returns `true` if the `Literal` is an integer or float literal with a
+    /// suffix, such as `5u8` or `1.0f32`.
+    fn is_suffixed(&self) -> bool {
+        internal::is_suffixed(self.parse_int(), || self.parse_float())
+    }
+
+    /// If the `Literal` is a string literal, returns it's value.
+    fn parse_string(&self) -> Option<String>;
+
+    /// If the `Literal` is a string literal, returns it's value. Otherwise,
+    /// returns an error describing why it could not be parsed.
+    fn try_parse_string(&self) -> Result<String, LiteralError>;
+
+    /// If the `Literal` is a string literal, returns it's value along with
+    /// whether it was written as a raw string. Unlike `parse_string`, this
+    /// accepts a suffix after the closing quote, such as `"foo"bar`.
+    fn parse_string_lit(&self) -> Option<StrLit>;
+
+    /// Like `parse_string`, but drops a leading byte order mark (`\u{FEFF}`)
+    /// from the value, which can be left behind when text is copy-pasted.
+    fn parse_string_trimmed_bom(&self) -> Option<String>;
+
+    /// Like `parse_string`, but returns a value shared with every identical
+    /// string which has been interned on this thread, so decoding the same
+    /// literal many times only allocates once. See `clear_interned_strings`.
+    ///
+    /// This is provided in terms of `raw_text`, so enabling the `intern`
+    /// feature doesn't require other implementations of `LiteralExt` to change.
+    #[cfg(feature = "intern")]
+    fn parse_interned_string(&self) -> Option<Arc<str>> {
+        internal::interned_str(&self.raw_text())
+    }
+
+    /// If the `Literal` is a string literal, returns the number of `char`s in
+    /// it's value, without allocating a `String` to hold it.
+    fn string_len(&self) -> Option<usize>;
+
+    /// If the `Literal` is a char literal, returns it's value.
+    fn parse_char(&self) -> Option<char>;
+
+    /// If the `Literal` is a char literal, returns it's value. Otherwise,
+    /// returns an error describing why it could not be parsed.
+    fn try_parse_char(&self) -> Result<char, LiteralError>;
+
+    /// If the `Literal` is a char literal, returns it's value along with how
+    /// it was escaped.
+    fn parse_char_lit(&self) -> Option<CharLit>;
+
+    /// If the `Literal` is a string literal, returns the UTF-8 bytes of it's
+    /// value. This does not accept byte strings, which are read with
+    /// `parse_bytes`, but allows the values of both to be handled alike.
+    fn parse_utf8_bytes(&self) -> Option<Vec<u8>> {
+        self.parse_string().map(String::into_bytes)
+    }
+
+    /// Returns `true` if the text of the `Literal` is a lifetime or a label,
+    /// such as `'a` or `'static`, which would otherwise look like the start of
+    /// a char literal.
+    ///
+    /// Lifetimes are not literals, so a real `Literal` token will never match.
+    /// Like `parse_bool`, this is only useful for types such as `DummyLiteral`
+    /// which wrap text that has been stringified from other tokens.
+    fn is_lifetime(&self) -> bool;
+
+    /// If the `Literal` is a string literal, returns an iterator over the
+    /// chars of it's value, which decodes each char as it is requested, so
+    /// a search can stop without decoding the rest of the string.
+    fn chars_iter(&self) -> Option<StrIter<'static>>;
+
+    /// If the `Literal` is a byte string literal, returns it's value.
+    fn parse_bytes(&self) -> Option<Vec<u8>>;
+
+    /// If the `Literal` is a byte string literal, returns it's value.
+    /// Otherwise, returns an error describing why it could not be parsed.
+    fn try_parse_bytes(&self) -> Result<Vec<u8>, LiteralError>;
+
+    /// If the `Literal` is a byte string literal, returns an iterator over
+    /// it's value, which decodes each byte as it is requested.
+    fn bytes_iter(&self) -> Option<ByteStrIter<'static>>;
+
+    /// If the `Literal` is a C string literal, returns it's value, followed by
+    /// the trailing NUL byte.
+    fn parse_cstr(&self) -> Option<Vec<u8>>;
+
+    /// If the `Literal` is a byte literal, returns it's value.
+    fn parse_byte(&self) -> Option<u8>;
+
+    /// If the `Literal` is a byte literal, returns it's value along with how
+    /// it was escaped.
+    fn parse_byte_lit(&self) -> Option<ByteLit>;
+
+    /// If the text of the `Literal` is `true` or `false`, returns it's value.
+    ///
+    /// These are identifiers rather than literals, so a real `Literal` token
+    /// will never match. This is only useful for types such as `DummyLiteral`
+    /// which wrap text that has been stringified from an identifier.
+    fn parse_bool(&self) -> Option<bool>;
+
+    /// If the `Literal` is an inner doc comment (`//!` or `/*!`), returns a
+    /// string with the text of the comment.
+    ///
+    /// This expects the comment's source text. The compiler passes doc
+    /// comments to procedural macros as `#[doc = "..."]` attributes instead,
+    /// whose string literals are accepted by `doc_from_attr`.
+    fn parse_inner_doc(&self) -> Option<String>;
+
+    /// If the `Literal` is an outer doc comment (`///` or `/**`), returns a
+    /// string with the text of the comment.
+    ///
+    /// Like `parse_inner_doc`, this expects the comment's source text.
+    fn parse_outer_doc(&self) -> Option<String>;
+
+    /// If the `Literal` is a doc comment of either kind, returns the text of
+    /// its body, without the `///`, `//!`, `/**`, or `/*!` delimiters. The `*`
+    /// used to align the lines of block comments is removed.
+    fn doc_text(&self) -> Option<String>;
+
+    /// Like `doc_text`, but if the `Literal` is a string literal, such as the
+    /// value of a `#[doc = "..."]` attribute, returns it's value as the text
+    /// of the documentation.
+    fn doc_from_attr(&self) -> Option<String>;
+
+    /// Determine which kind of literal the `Literal` is by inspecting its
+    /// leading characters, without running any parsers. This does not check
+    /// that the literal is well formed.
+    fn kind(&self) -> LiteralKind;
+
+    /// Returns the decoded value of the `Literal`, whatever kind it is. The
+    /// parsers are tried in the order of the variants of `LitValue`, so
+    /// literals such as `5` which could be read as multiple kinds are returned
+    /// as the first kind which matches. As with `LitValue::try_from`, integers
+    /// which are out of range for their suffix, such as `256u8`, are `None`.
+    fn value(&self) -> Option<LitValue>;
+
+    /// Get the text of the `Literal`, as it was written in source, without
+    /// parsing it. This is useful for diagnostics. There is no borrowing form
+    /// of this method, as `Literal` types only expose their text through
+    /// `Display`.
+    fn raw_text(&self) -> String;
+
+    /// Like `value`, but also returns the text the value was parsed from.
+    fn parse_full(&self) -> Option<ParsedLit>;
+
+    /// Like `value`, but makes a best effort to decode a malformed literal,
+    /// returning whatever could be decoded along with a diagnostic for each
+    /// problem found. Malformed escapes in strings and chars are replaced with
+    /// `U+FFFD`, and in byte strings and bytes, they are skipped. Other kinds
+    /// of literal, and raw strings, are not recovered, so their value is
+    /// `None`, except for integers which are out of range for their suffix,
+    /// such as `256u8`. A well formed literal has no diagnostics.
+    fn parse_lenient(&self) -> (Option<LitValue>, Vec<LiteralDiagnostic>);
+
+    /// If the `Literal` is an integer literal, returns it's value. Otherwise,
+    /// returns the kind of literal it is, for use in error messages such as
+    /// "expected an integer, found a string". A malformed integer literal is
+    /// reported as `LiteralKind::Int`.
+    fn expect_int(&self) -> Result<IntLit, LiteralKind> {
+        self.parse_int().ok_or_else(|| self.kind())
+    }
+
+    /// Like `expect_int`, but for a floating point literal.
+    fn expect_float(&self) -> Result<FloatLit, LiteralKind> {
+        self.parse_float().ok_or_else(|| self.kind())
+    }
+
+    /// Like `expect_int`, but for a string literal.
+    fn expect_string(&self) -> Result<String, LiteralKind> {
+        self.parse_string().ok_or_else(|| self.kind())
+    }
+
+    /// Like `expect_int`, but for a byte string literal.
+    fn expect_bytes(&self) -> Result<Vec<u8>, LiteralKind> {
+        self.parse_bytes().ok_or_else(|| self.kind())
+    }
+
+    /// Like `expect_int`, but for a char literal.
+    fn expect_char(&self) -> Result<char, LiteralKind> {
+        self.parse_char().ok_or_else(|| self.kind())
+    }
+
+    /// Like `expect_int`, but for a byte literal.
+    fn expect_byte(&self) -> Result<u8, LiteralKind> {
+        self.parse_byte().ok_or_else(|| self.kind())
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! impl_literal {
+    () => {
+        fn parse_int(&self) -> Option<IntLit> {
+            $crate::internal::int_lit(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn parse_float(&self) -> Option<FloatLit> {
+            $crate::internal::float_lit(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn parse_float_forgiving(&self) -> Option<FloatLit> {
+            $crate::internal::float_lit_forgiving(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn parse_string(&self) -> Option<String> {
+            self.try_parse_string().ok()
+        }
+
+        fn try_parse_string(&self) -> Result<String, LiteralError> {
+            $crate::internal::str_lit(&$crate::internal::token_text(self))
+        }
+
+        fn parse_string_lit(&self) -> Option<StrLit> {
+            $crate::internal::string_lit(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn parse_string_trimmed_bom(&self) -> Option<String> {
+            $crate::internal::str_lit_trimmed_bom(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn string_len(&self) -> Option<usize> {
+            $crate::internal::str_len(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn parse_char(&self) -> Option<char> {
+            self.try_parse_char().ok()
+        }
+
+        fn try_parse_char(&self) -> Result<char, LiteralError> {
+            $crate::internal::char_lit(&$crate::internal::token_text(self))
+        }
+
+        fn parse_char_lit(&self) -> Option<CharLit> {
+            $crate::internal::char_lit_escape(&$crate::internal::token_text(self)).ok()
+        }
+
+        fn is_lifetime(&self) -> bool {
+            $crate::internal::is_lifetime(&$crate::internal::token_text(self))
+        }
+
+        fn chars_iter(&self) -> Option<StrIter<'static>> {
+            $crate::internal::str_iter($crate::internal::token_text(self).into())
+        }
+
+        fn parse_bytes(&self) -> Option<Vec<u8>> {
+            self.try_parse_bytes().ok()
+        }
+
+        fn try_parse_bytes(&self) -> Result<Vec<u8>, LiteralError> {
+            $crate::internal::try_byte_str_lit(&$crate::internal::token_text(self))
+        }
+
+        fn bytes_iter(&self) -> Option<ByteStrIter<'static>> {
+            $crate::internal::byte_str_iter($crate::internal::token_text(self).into())
+        }
+
+        fn parse_cstr(&self) -> Option<Vec<u8>> {
+            $crate::internal::cstr_lit(&$crate::internal::token_text(self))
+        }
+
+        fn parse_byte(&self) -> Option<u8> {
+            $crate::internal::byte_lit(&$crate::internal::token_text(self))
+        }
+
+        fn parse_byte_lit(&self) -> Option<ByteLit> {
+            $crate::internal::byte_lit_escape(&$crate::internal::token_text(self))
+        }
+
+        fn parse_bool(&self) -> Option<bool> {
+            $crate::internal::bool_lit(&$crate::internal::token_text(self))
+        }
+
+        fn parse_inner_doc(&self) -> Option<String> {
+            $crate::internal::inner_doc($crate::internal::token_text(self))
+        }
+
+        fn parse_outer_doc(&self) -> Option<String> {
+            $crate::internal::outer_doc($crate::internal::token_text(self))
+        }
+
+        fn doc_text(&self) -> Option<String> {
+            $crate::internal::doc_text(&$crate::internal::token_text(self))
+        }
+
+        fn doc_from_attr(&self) -> Option<String> {
+            $crate::internal::doc_from_attr(&$crate::internal::token_text(self))
+        }
+
+        fn kind(&self) -> LiteralKind {
+            $crate::internal::lit_kind(&$crate::internal::token_text(self))
+        }
+
+        fn value(&self) -> Option<LitValue> {
+            $crate::internal::lit_value(&$crate::internal::token_text(self))
+        }
+
+        fn raw_text(&self) -> String {
+            $crate::internal::token_text(self)
+        }
+
+        fn parse_full(&self) -> Option<ParsedLit> {
+            $crate::internal::parsed_lit($crate::internal::token_text(self))
+        }
+
+        fn parse_lenient(&self) -> (Option<LitValue>, Vec<LiteralDiagnostic>) {
+            $crate::internal::lit_lenient(&$crate::internal::token_text(self))
+        }
+    }
+}
+
+#[cfg(feature = "dummy")]
+impl<T: fmt::Display> LiteralExt for DummyLiteral<T> {
+    impl_literal!();
+}
+
+#[cfg(feature = "proc-macro")]
+impl LiteralExt for proc_macro::Literal {
+    impl_literal!();
 }
 
 #[cfg(feature = "proc-macro2")]
 impl LiteralExt for proc_macro2::Literal {
     impl_literal!();
 }
+
+/// An error describing why an `IntLit` could not be converted to a
+/// `proc_macro2::Literal` token.
+#[cfg(feature = "proc-macro2")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IntTokenError {
+    /// The value is negative, such as one parsed by `parse::signed_int`. A
+    /// negative number is a `-` token followed by a literal, so it is not a
+    /// single token, and `parse_int` would reject the token's text.
+    Negative,
+    /// The literal has a custom suffix, accepted by `parse::int_with_suffixes`,
+    /// which `proc_macro2` cannot lex as part of a literal token.
+    CustomSuffix,
+}
+
+#[cfg(feature = "proc-macro2")]
+impl fmt::Display for IntTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IntTokenError::Negative => f.write_str("negative integer is not a single token"),
+            IntTokenError::CustomSuffix => f.write_str("custom suffix cannot be lexed as a token"),
+        }
+    }
+}
+
+#[cfg(all(feature = "proc-macro2", feature = "std"))]
+impl error::Error for IntTokenError {}
+
+/// Converts the integer to a token, with the constructor for its suffix, such
+/// as `Literal::u8`. Values which those constructors cannot represent, such as
+/// `u128` values or values which overflow their suffix, are written with their
+/// original digits, in the base they were written in.
+#[cfg(feature = "proc-macro2")]
+impl TryFrom<IntLit> for proc_macro2::Literal {
+    type Error = IntTokenError;
+    fn try_from(lit: IntLit) -> Result<proc_macro2::Literal, IntTokenError> {
+        use proc_macro2::Literal;
+
+        if lit.negative {
+            return Err(IntTokenError::Negative);
+        }
+        let token = match lit.suffix_ty() {
+            Some(IntSuffix::U8) => lit.as_u8().map(Literal::u8),
+            Some(IntSuffix::I8) => lit.as_i8().map(Literal::i8),
+            Some(IntSuffix::U16) => lit.as_u16().map(Literal::u16),
+            Some(IntSuffix::I16) => lit.as_i16().map(Literal::i16),
+            Some(IntSuffix::U32) => lit.as_u32().map(Literal::u32),
+            Some(IntSuffix::I32) => lit.as_i32().map(Literal::i32),
+            Some(IntSuffix::U64) => lit.as_u64().map(Literal::u64),
+            Some(IntSuffix::I64) => lit.as_i64().map(Literal::i64),
+            Some(IntSuffix::Usize) => lit.as_usize().map(Literal::usize),
+            Some(IntSuffix::Isize) => lit.as_isize().map(Literal::isize),
+            // NOTE: `proc_macro2` has no constructors for 128-bit integers.
+            Some(IntSuffix::U128) | Some(IntSuffix::I128) => None,
+            None if lit.suffix.is_empty() => lit.as_i64().map(Literal::integer),
+            None => None,
+        };
+        match token.or_else(|| internal::lex_literal(&lit.to_string())) {
+            Some(token) => Ok(token),
+            None => Err(IntTokenError::CustomSuffix),
+        }
+    }
+}
+
+/// Converts the float to a token, with the constructor for its suffix, such as
+/// `Literal::f32`. Values which are too large to be finite are written with
+/// their original digits. A negative value, which can only be built with
+/// `FloatLit::new`, is written with a leading `-`, so the token's text is not
+/// accepted by `parse_float`.
+#[cfg(feature = "proc-macro2")]
+impl From<FloatLit> for proc_macro2::Literal {
+    fn from(lit: FloatLit) -> proc_macro2::Literal {
+        use proc_macro2::Literal;
+
+        let finite = match lit.suffix_ty() {
+            Some(FloatSuffix::F32) => (lit.val as f32).is_finite(),
+            _ => lit.val.is_finite(),
+        };
+        if !finite {
+            let mut text = lit.repr.to_string();
+            text.push_str(lit.suffix);
+            if let Some(token) = internal::lex_literal(&text) {
+                return token;
+            }
+        }
+        match lit.suffix_ty() {
+            Some(FloatSuffix::F32) => Literal::f32(lit.val as f32),
+            Some(FloatSuffix::F64) => Literal::f64(lit.val),
+            None => Literal::float(lit.val),
+        }
+    }
+}
+
+/// An extension trait for tokens which know their location in the source, to
+/// decode a literal along with the span to report errors about it at.
+#[cfg(any(feature = "proc-macro", feature = "proc-macro2"))]
+pub trait LiteralExtSpanned {
+    /// The type of the span carried by the token.
+    type Span;
+
+    /// Returns the decoded value of the literal, as `LiteralExt::value` does,
+    /// along with its span.
+    fn parse_with_span(&self) -> (Option<LitValue>, Self::Span);
+}
+
+#[cfg(feature = "proc-macro")]
+impl LiteralExtSpanned for proc_macro::Literal {
+    type Span = proc_macro::Span;
+
+    fn parse_with_span(&self) -> (Option<LitValue>, proc_macro::Span) {
+        (self.value(), self.span())
+    }
+}
+
+/// `proc_macro2::Literal` does not carry a span, so this is implemented on the
+/// `TokenTree` which holds it. Trees which are not literals have no value.
+#[cfg(feature = "proc-macro2")]
+impl LiteralExtSpanned for proc_macro2::TokenTree {
+    type Span = proc_macro2::Span;
+
+    fn parse_with_span(&self) -> (Option<LitValue>, proc_macro2::Span) {
+        let value = match self.kind {
+            proc_macro2::TokenNode::Literal(ref lit) => lit.value(),
+            _ => None,
+        };
+        (value, self.span)
+    }
+}
+
+/// Empty this thread's string interner, which is used by
+/// `LiteralExt::parse_interned_string`. Interned strings are otherwise kept
+/// until the thread exits, even once they are no longer used, so long-running
+/// processes which decode many distinct strings may want to call this between
+/// jobs. Values which were already returned remain valid, but are no longer
+/// shared with strings interned after the call.
+#[cfg(feature = "intern")]
+pub fn clear_interned_strings() {
+    internal::clear_interned();
+}
+
+/// Returns an iterator over the decoded value of every literal in the stream,
+/// along with its span, including those nested in groups. Tokens which are
+/// not literals, and literals which cannot be decoded, are skipped.
+#[cfg(feature = "proc-macro2")]
+pub fn literals(stream: proc_macro2::TokenStream)
+                -> impl Iterator<Item = (proc_macro2::Span, LitValue)> {
+    let mut stack = Vec::new();
+    stack.push(stream.into_iter());
+    core::iter::from_fn(move || {
+        while let Some(iter) = stack.last_mut() {
+            let tree = match iter.next() {
+                Some(tree) => tree,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            match tree.kind {
+                proc_macro2::TokenNode::Group(_, ref inner) => {
+                    stack.push(inner.clone().into_iter());
+                }
+                proc_macro2::TokenNode::Literal(_) => {
+                    if let (Some(value), span) = tree.parse_with_span() {
+                        return Some((span, value));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    })
+}
+
+/// The float variants are built from the digits and suffix which `syn` has
+/// already lexed, rather than by reparsing the token. The other variants,
+/// including integers, are parsed from their token, so they report the same
+/// base as the `proc_macro2` impl does.
+#[cfg(feature = "syn")]
+impl LiteralExt for syn::Lit {
+    fn parse_int(&self) -> Option<IntLit> {
+        match *self {
+            syn::Lit::Int(ref lit) => internal::signed_int_lit(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::int_lit(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_float(&self) -> Option<FloatLit> {
+        match *self {
+            syn::Lit::Float(ref lit) => {
+                let text = lit.token().to_string();
+                internal::float_from_parts(lit.base10_digits(), lit.suffix(), &text)
+            }
+            // NOTE: `syn` lexes `5f32` as an integer with an `f32` suffix.
+            syn::Lit::Int(ref lit) if !lit.suffix().is_empty() => {
+                let text = lit.token().to_string();
+                internal::float_from_parts(lit.base10_digits(), lit.suffix(), &text)
+            }
+            syn::Lit::Verbatim(ref lit) => internal::float_lit(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_float_forgiving(&self) -> Option<FloatLit> {
+        match *self {
+            syn::Lit::Float(ref lit) => {
+                internal::float_lit_forgiving(&lit.token().to_string()).ok()
+            }
+            syn::Lit::Int(ref lit) => internal::float_lit_forgiving(&lit.token().to_string()).ok(),
+            syn::Lit::Verbatim(ref lit) => internal::float_lit_forgiving(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_string(&self) -> Option<String> {
+        self.try_parse_string().ok()
+    }
+
+    fn try_parse_string(&self) -> Result<String, LiteralError> {
+        match *self {
+            syn::Lit::Str(ref lit) => internal::str_lit(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::str_lit(&lit.to_string()),
+            _ => Err(LiteralError::NotThisKind),
+        }
+    }
+
+    fn parse_string_lit(&self) -> Option<StrLit> {
+        match *self {
+            syn::Lit::Str(ref lit) => internal::string_lit(&lit.token().to_string()).ok(),
+            syn::Lit::Verbatim(ref lit) => internal::string_lit(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_string_trimmed_bom(&self) -> Option<String> {
+        match *self {
+            syn::Lit::Str(ref lit) => internal::str_lit_trimmed_bom(&lit.token().to_string()).ok(),
+            syn::Lit::Verbatim(ref lit) => internal::str_lit_trimmed_bom(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn string_len(&self) -> Option<usize> {
+        match *self {
+            syn::Lit::Str(ref lit) => internal::str_len(&lit.token().to_string()).ok(),
+            syn::Lit::Verbatim(ref lit) => internal::str_len(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_char(&self) -> Option<char> {
+        self.try_parse_char().ok()
+    }
+
+    fn try_parse_char(&self) -> Result<char, LiteralError> {
+        match *self {
+            syn::Lit::Char(ref lit) => internal::char_lit(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::char_lit(&lit.to_string()),
+            _ => Err(LiteralError::NotThisKind),
+        }
+    }
+
+    fn parse_char_lit(&self) -> Option<CharLit> {
+        match *self {
+            syn::Lit::Char(ref lit) => internal::char_lit_escape(&lit.token().to_string()).ok(),
+            syn::Lit::Verbatim(ref lit) => internal::char_lit_escape(&lit.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    fn is_lifetime(&self) -> bool {
+        match *self {
+            syn::Lit::Verbatim(ref lit) => internal::is_lifetime(&lit.to_string()),
+            _ => false,
+        }
+    }
+
+    fn parse_bytes(&self) -> Option<Vec<u8>> {
+        self.try_parse_bytes().ok()
+    }
+
+    fn try_parse_bytes(&self) -> Result<Vec<u8>, LiteralError> {
+        match *self {
+            syn::Lit::ByteStr(ref lit) => internal::try_byte_str_lit(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::try_byte_str_lit(&lit.to_string()),
+            _ => Err(LiteralError::NotThisKind),
+        }
+    }
+
+    fn chars_iter(&self) -> Option<StrIter<'static>> {
+        match *self {
+            syn::Lit::Str(ref lit) => internal::str_iter(lit.token().to_string().into()),
+            syn::Lit::Verbatim(ref lit) => internal::str_iter(lit.to_string().into()),
+            _ => None,
+        }
+    }
+
+    fn bytes_iter(&self) -> Option<ByteStrIter<'static>> {
+        match *self {
+            syn::Lit::ByteStr(ref lit) => internal::byte_str_iter(lit.token().to_string().into()),
+            syn::Lit::Verbatim(ref lit) => internal::byte_str_iter(lit.to_string().into()),
+            _ => None,
+        }
+    }
+
+    fn parse_cstr(&self) -> Option<Vec<u8>> {
+        match *self {
+            // NOTE: `syn` has no variant for C strings.
+            syn::Lit::Verbatim(ref lit) => internal::cstr_lit(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_byte(&self) -> Option<u8> {
+        match *self {
+            syn::Lit::Byte(ref lit) => internal::byte_lit(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::byte_lit(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_byte_lit(&self) -> Option<ByteLit> {
+        match *self {
+            syn::Lit::Byte(ref lit) => internal::byte_lit_escape(&lit.token().to_string()),
+            syn::Lit::Verbatim(ref lit) => internal::byte_lit_escape(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_bool(&self) -> Option<bool> {
+        match *self {
+            syn::Lit::Bool(ref lit) => Some(lit.value),
+            _ => None,
+        }
+    }
+
+    fn parse_inner_doc(&self) -> Option<String> {
+        match *self {
+            syn::Lit::Verbatim(ref lit) => internal::inner_doc(lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_outer_doc(&self) -> Option<String> {
+        match *self {
+            syn::Lit::Verbatim(ref lit) => internal::outer_doc(lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn doc_text(&self) -> Option<String> {
+        match *self {
+            syn::Lit::Verbatim(ref lit) => internal::doc_text(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn doc_from_attr(&self) -> Option<String> {
+        match *self {
+            syn::Lit::Str(_) => self.parse_string(),
+            syn::Lit::Verbatim(ref lit) => internal::doc_from_attr(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> LiteralKind {
+        match *self {
+            syn::Lit::Int(ref lit) => match lit.suffix() {
+                "f32" | "f64" => LiteralKind::Float,
+                _ => LiteralKind::Int,
+            },
+            syn::Lit::Float(_) => LiteralKind::Float,
+            syn::Lit::Str(_) => LiteralKind::Str,
+            syn::Lit::ByteStr(_) => LiteralKind::ByteStr,
+            syn::Lit::Char(_) => LiteralKind::Char,
+            syn::Lit::Byte(_) => LiteralKind::Byte,
+            syn::Lit::Verbatim(ref lit) => internal::lit_kind(&lit.to_string()),
+            _ => LiteralKind::Unknown,
+        }
+    }
+
+    fn value(&self) -> Option<LitValue> {
+        match *self {
+            syn::Lit::Int(_) | syn::Lit::Float(_) => match self.parse_int() {
+                Some(ref int) if int.declared_overflow() => None,
+                Some(int) => Some(LitValue::Int(int)),
+                None => self.parse_float().map(LitValue::Float),
+            },
+            syn::Lit::Str(_) => self.parse_string().map(LitValue::Str),
+            syn::Lit::ByteStr(_) => self.parse_bytes().map(LitValue::ByteStr),
+            syn::Lit::Char(_) => self.parse_char().map(LitValue::Char),
+            syn::Lit::Byte(_) => self.parse_byte().map(LitValue::Byte),
+            syn::Lit::Verbatim(ref lit) => internal::lit_value(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn raw_text(&self) -> String {
+        match *self {
+            syn::Lit::Str(ref lit) => lit.token().to_string(),
+            syn::Lit::ByteStr(ref lit) => lit.token().to_string(),
+            syn::Lit::Byte(ref lit) => lit.token().to_string(),
+            syn::Lit::Char(ref lit) => lit.token().to_string(),
+            syn::Lit::Int(ref lit) => lit.token().to_string(),
+            syn::Lit::Float(ref lit) => lit.token().to_string(),
+            syn::Lit::Bool(ref lit) => lit.value.to_string(),
+            syn::Lit::Verbatim(ref lit) => lit.to_string(),
+        }
+    }
+
+    fn parse_full(&self) -> Option<ParsedLit> {
+        self.value().map(|value| ParsedLit { text: self.raw_text(), value })
+    }
+
+    fn parse_lenient(&self) -> (Option<LitValue>, Vec<LiteralDiagnostic>) {
+        match self.value() {
+            Some(value) => (Some(value), Vec::new()),
+            None => internal::lit_lenient(&self.raw_text()),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Functions for interpreting the source text of a literal directly, without
+//! a `Literal` token. These are useful for tools which read literals out of
+//! text, and are available regardless of which features are enabled.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "intern")]
+use std::sync::Arc;
+
+use {IntLit, FloatLit, StrLit, CharLit, ByteLit, ByteStrIter, StrIter, LiteralError,
+     LiteralDiagnostic, LiteralKind, LitValue, ParsedLit};
+use internal;
+
+/// If the text is an integer literal, returns its value.
+pub fn int(s: &str) -> Option<IntLit> {
+    internal::int_lit(s).ok()
+}
+
+/// Like `int`, but also accepts the uppercase base prefixes `0X`, `0O` and
+/// `0B`, which rustc rejects but some code generators emit, and a leading `-`
+/// or `+` sign, as `signed_int` does.
+pub fn int_lenient(s: &str) -> Option<IntLit> {
+    internal::int_lit_lenient(s).ok()
+}
+
+/// Like `int`, but accepts only the given suffixes instead of the Rust integer
+/// suffixes. This is useful for languages with unit suffixes, such as `5px`.
+/// The suffixes need not be Rust integer types, in which case the literal's
+/// `suffix` is the custom suffix, and it cannot be extracted with `as_*`.
+pub fn int_with_suffixes(s: &str, allowed: &[&str]) -> Option<IntLit> {
+    internal::int_lit_with_suffixes(s, allowed).ok()
+}
+
+/// If the text is made up of digits in the given radix, which must be from 2
+/// to 36, returns their value. The digits may be separated by `_`, but there
+/// is no base prefix or suffix, so this is useful for reading integers out of
+/// other languages, such as base 36 IDs. Letters are digits from 10 upwards,
+/// in either case, so `Zz` is 1295 in base 36.
+pub fn int_radix(s: &str, radix: u32) -> Option<IntLit> {
+    internal::int_radix_lit(s, radix).ok()
+}
+
+/// If the text is an integer literal, optionally preceded by a `-` or `+`
+/// sign, returns its value. `Literal` tokens never contain a sign, as Rust
+/// tokenizes `-5` as a `-` followed by `5`, so this is only useful for text
+/// where the sign has already been joined onto the literal.
+pub fn signed_int(s: &str) -> Option<IntLit> {
+    internal::signed_int_lit(s)
+}
+
+/// If the text is a floating point literal, returns its value.
+pub fn float(s: &str) -> Option<FloatLit> {
+    internal::float_lit(s).ok()
+}
+
+/// If the text is `inf`, `-inf` or `NaN`, as written by the `Display` impl of
+/// `f64` for values which have no literal form, returns the value. Such text
+/// is never a float literal, so `float` always rejects it.
+pub fn float_special(s: &str) -> Option<f64> {
+    internal::float_special(s)
+}
+
+/// Like `float`, but also accepts an unsuffixed decimal integer, such as `1`,
+/// for text which is known to be a float.
+pub fn float_forgiving(s: &str) -> Option<FloatLit> {
+    internal::float_lit_forgiving(s).ok()
+}
+
+/// If the text is an integer or float literal, returns it's value as an `f64`,
+/// whatever it's suffix is. Large integers lose precision, as with
+/// `LiteralExt::parse_number_f64`.
+pub fn number_f64(s: &str) -> Option<f64> {
+    internal::number_f64(int(s), || float(s))
+}
+
+/// Returns `true` if the text is an integer or float literal with a suffix.
+pub fn is_suffixed(s: &str) -> bool {
+    internal::is_suffixed(int(s), || float(s))
+}
+
+/// If the text is a string literal, returns it's value.
+pub fn string(s: &str) -> Option<String> {
+    internal::str_lit(s).ok()
+}
+
+/// If the text is a string literal, returns it's value. Otherwise, returns an
+/// error describing why it could not be parsed.
+pub fn try_string(s: &str) -> Result<String, LiteralError> {
+    internal::str_lit(s)
+}
+
+/// If the text is a raw string, raw byte string or raw C string literal,
+/// returns the number of `#`s it is delimited by, such as 1 for `r#"a"#`.
+pub fn raw_hash_count(s: &str) -> Option<usize> {
+    internal::raw_hash_count(s)
+}
+
+/// If the text is a string literal, returns it's value along with whether it
+/// was written as a raw string. Unlike `string`, this accepts a suffix after
+/// the closing quote, such as `"foo"bar`.
+pub fn string_lit(s: &str) -> Option<StrLit> {
+    internal::string_lit(s).ok()
+}
+
+/// If each of the texts is a string literal, returns their values joined
+/// together, like `concat!`. Returns `None` if any of them is not a string,
+/// including if it is a byte string.
+pub fn concat_strings(lits: &[&str]) -> Option<String> {
+    let mut out = String::new();
+    for lit in lits {
+        out.push_str(&internal::str_lit(lit).ok()?);
+    }
+    Some(out)
+}
+
+/// Like `string`, but drops a leading byte order mark (`\u{FEFF}`) from the
+/// value.
+pub fn string_trimmed_bom(s: &str) -> Option<String> {
+    internal::str_lit_trimmed_bom(s).ok()
+}
+
+/// If the text is a string literal, returns the number of `char`s in it's
+/// value, without allocating a `String` to hold it.
+pub fn string_len(s: &str) -> Option<usize> {
+    internal::str_len(s).ok()
+}
+
+/// Like `string`, but returns the value shared with every identical string
+/// which has been interned on this thread.
+#[cfg(feature = "intern")]
+pub fn interned_string(s: &str) -> Option<Arc<str>> {
+    internal::interned_str(s)
+}
+
+/// If the text is a string literal, returns it's value. The value borrows from
+/// the text when no unescaping is required, such as for raw strings.
+pub fn string_cow(s: &str) -> Option<Cow<'_, str>> {
+    internal::str_lit_cow(s).ok()
+}
+
+/// If the text is a char literal, returns it's value.
+pub fn char(s: &str) -> Option<char> {
+    internal::char_lit(s).ok()
+}
+
+/// If the text is a char literal, returns it's value. Otherwise, returns an
+/// error describing why it could not be parsed.
+pub fn try_char(s: &str) -> Result<char, LiteralError> {
+    internal::char_lit(s)
+}
+
+/// If the text is a char literal, returns it's value along with how it was
+/// escaped.
+pub fn char_lit(s: &str) -> Option<CharLit> {
+    internal::char_lit_escape(s).ok()
+}
+
+/// If the text is a string literal, returns the UTF-8 bytes of it's value.
+pub fn utf8_bytes(s: &str) -> Option<Vec<u8>> {
+    string(s).map(String::into_bytes)
+}
+
+/// If the text is a string literal, returns an iterator over the chars of
+/// it's value, which borrows from the text and decodes each char as it is
+/// requested.
+pub fn chars_iter(s: &str) -> Option<StrIter<'_>> {
+    internal::str_iter(Cow::Borrowed(s))
+}
+
+/// If the text is a byte string literal, returns it's value.
+pub fn bytes(s: &str) -> Option<Vec<u8>> {
+    internal::byte_str_lit(s)
+}
+
+/// If the text is a byte string literal, returns it's value. Otherwise,
+/// returns an error describing why it could not be parsed.
+pub fn try_bytes(s: &str) -> Result<Vec<u8>, LiteralError> {
+    internal::try_byte_str_lit(s)
+}
+
+/// If the text is a byte string literal, returns an iterator over it's value,
+/// which borrows from the text and decodes each byte as it is requested.
+pub fn bytes_iter(s: &str) -> Option<ByteStrIter<'_>> {
+    internal::byte_str_iter(Cow::Borrowed(s))
+}
+
+/// If the text is a C string literal, returns it's value, followed by the
+/// trailing NUL byte.
+pub fn cstr(s: &str) -> Option<Vec<u8>> {
+    internal::cstr_lit(s)
+}
+
+/// If the text is a byte literal, returns it's value.
+pub fn byte(s: &str) -> Option<u8> {
+    internal::byte_lit(s)
+}
+
+/// If the text is a byte literal, returns it's value along with how it was
+/// escaped.
+pub fn byte_lit(s: &str) -> Option<ByteLit> {
+    internal::byte_lit_escape(s)
+}
+
+/// If the text is `true` or `false`, returns it's value.
+pub fn bool(s: &str) -> Option<bool> {
+    internal::bool_lit(s)
+}
+
+/// If the text is an inner doc comment (`//!` or `/*!`), returns a string with
+/// the text of the comment.
+pub fn inner_doc(s: &str) -> Option<String> {
+    internal::inner_doc(s.to_string())
+}
+
+/// If the text is an outer doc comment (`///` or `/**`), returns a string with
+/// the text of the comment.
+pub fn outer_doc(s: &str) -> Option<String> {
+    internal::outer_doc(s.to_string())
+}
+
+/// If the text is a doc comment of either kind, returns the text of its body,
+/// without the comment delimiters.
+pub fn doc_text(s: &str) -> Option<String> {
+    internal::doc_text(s)
+}
+
+/// Like `doc_text`, but if the text is a string literal, such as the value of
+/// a `#[doc = "..."]` attribute, returns it's value.
+pub fn doc_from_attr(s: &str) -> Option<String> {
+    internal::doc_from_attr(s)
+}
+
+/// Returns `true` if the text begins like a string literal, either `"` or a
+/// raw `r"` or `r#"`. This does not check that the literal is well formed.
+pub fn is_string(s: &str) -> bool {
+    matches!(internal::str_prefix(s), Some(("", _)) | Some(("r", _)))
+}
+
+/// Returns `true` if the text begins like a byte string literal, either `b"`
+/// or a raw `br"` or `br#"`. This does not check that the literal is well
+/// formed.
+pub fn is_byte_string(s: &str) -> bool {
+    matches!(internal::str_prefix(s), Some(("b", _)) | Some(("br", _)))
+}
+
+/// Returns `true` if the text begins like a C string literal, either `c"` or
+/// a raw `cr"` or `cr#"`. This does not check that the literal is well formed.
+pub fn is_c_string(s: &str) -> bool {
+    matches!(internal::str_prefix(s), Some(("c", _)) | Some(("cr", _)))
+}
+
+/// Returns `true` if the text begins like a raw string, byte string, or C
+/// string literal, such as `r#"`, `br"` or `cr"`. This does not check that
+/// the literal is well formed.
+pub fn is_raw(s: &str) -> bool {
+    matches!(internal::str_prefix(s), Some((_, true)))
+}
+
+/// Returns `true` if the text is a lifetime or label, such as `'a`, rather than
+/// a char literal.
+pub fn is_lifetime(s: &str) -> bool {
+    internal::is_lifetime(s)
+}
+
+/// Determine which kind of literal the text is by inspecting its leading
+/// characters. This does not check that the literal is well formed.
+pub fn kind(s: &str) -> LiteralKind {
+    internal::lit_kind(s)
+}
+
+/// Returns the decoded value of the text, whatever kind of literal it is.
+pub fn value(s: &str) -> Option<LitValue> {
+    internal::lit_value(s)
+}
+
+/// Like `value`, but also returns a copy of the text the value was parsed from.
+pub fn full(s: &str) -> Option<ParsedLit> {
+    internal::parsed_lit(s.to_string())
+}
+
+/// Like `value`, but if the text is a malformed string, byte string, char or
+/// byte literal, makes a best effort to recover its value. Returns a
+/// diagnostic for each problem found, which is empty only if the text is a
+/// well formed literal.
+pub fn lenient(s: &str) -> (Option<LitValue>, Vec<LiteralDiagnostic>) {
+    internal::lit_lenient(s)
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse() {
+    assert_eq!(int("0xFFu8").and_then(|i| i.as_u8()), Some(255));
+    assert_eq!(int("0x_FFu8").and_then(|i| i.suffix_offset()), Some(5));
+    assert_eq!(signed_int("-0xFFu8").and_then(|i| i.suffix_offset()), Some(5));
+    assert_eq!(int("0XFF"), None);
+    assert_eq!(int_lenient("0XFF").and_then(|i| i.as_u8()), Some(255));
+    assert_eq!(int_lenient("0O17").map(|i| i.base()), Some(8));
+    assert_eq!(int_lenient("0B101u8").and_then(|i| i.as_u8()), Some(5));
+    assert_eq!(int_lenient("0xff").and_then(|i| i.as_u8()), Some(255));
+    assert_eq!(int_radix("zz", 36).and_then(|i| i.as_u16()), Some(1295));
+    assert_eq!(int_with_suffixes("5px", &["px"]).map(|i| i.suffix().to_string()),
+               Some("px".to_string()));
+    assert_eq!(float("1.5f32").and_then(|f| f.as_f32()), Some(1.5));
+    assert_eq!(float_forgiving("1").and_then(|f| f.as_f64()), Some(1.0));
+    assert_eq!(float_special("-inf"), Some(f64::NEG_INFINITY));
+    assert!(float_special("NaN").is_some_and(f64::is_nan));
+    assert_eq!(float_special("1.0"), None);
+    assert_eq!(number_f64("5u8"), Some(5.0));
+    assert!(is_suffixed("5u8") && !is_suffixed("5"));
+    assert_eq!(string(r#""a\nb""#), Some("a\nb".to_string()));
+    assert_eq!(try_string("5"), Err(LiteralError::NotThisKind));
+    match string_cow(r##"r#"a\nb"#"##) {
+        Some(Cow::Borrowed(s)) => assert_eq!(s, "a\\nb"),
+        x => panic!("expected a borrowed string, got {:?}", x),
+    }
+    match string_cow(r#""abc""#) {
+        Some(Cow::Borrowed(s)) => assert_eq!(s, "abc"),
+        x => panic!("expected a borrowed string, got {:?}", x),
+    }
+    match string_cow(r#""a\nb""#) {
+        Some(Cow::Owned(s)) => assert_eq!(s, "a\nb"),
+        x => panic!("expected an owned string, got {:?}", x),
+    }
+    assert_eq!(string_cow(r#""a"b""#), None);
+    assert_eq!(string_lit(r##"r#"a"#"##).map(|s| (s.is_raw(), s.hash_count())), Some((true, 1)));
+    assert_eq!(raw_hash_count(r###"br##"a"##"###), Some(2));
+    assert_eq!(raw_hash_count(r#""a""#), None);
+    assert_eq!(string_trimmed_bom("\"\u{FEFF}a\""), Some("a".to_string()));
+    assert_eq!(concat_strings(&[r#""a\n""#, r##"r#"b"#"##]), Some("a\nb".to_string()));
+    assert_eq!(concat_strings(&[]), Some(String::new()));
+    assert_eq!(concat_strings(&[r#""a""#, r#"b"b""#]), None);
+    assert_eq!(concat_strings(&[r#""a""#, "5"]), None);
+    assert_eq!(string_len(r#""a\u{e9}\n""#), Some(3));
+    assert_eq!(char("'a'"), Some('a'));
+    assert_eq!(char_lit(r"'\x41'").map(|c| c.value()), Some('A'));
+    assert_eq!(utf8_bytes(r#""\u{e9}""#), Some(vec![0xC3, 0xA9]));
+    assert_eq!(bytes(r#"b"\xFF""#), Some(vec![0xFF]));
+    assert_eq!(chars_iter(r#""a\u{e9}""#).map(|i| i.collect::<String>()),
+               Some("a\u{e9}".to_string()));
+    assert_eq!(bytes_iter(r#"b"a\x00""#).map(|i| i.collect::<Vec<_>>()), Some(vec![b'a', 0]));
+    assert_eq!(cstr(r#"c"a""#), Some(b"a\0".to_vec()));
+    assert_eq!(byte("b'a'"), Some(b'a'));
+    assert_eq!(byte_lit(r"b'\x41'").map(|b| b.value()), Some(b'A'));
+    assert_eq!(bool("true"), Some(true));
+    assert_eq!(inner_doc("//! a"), Some("//! a".to_string()));
+    assert_eq!(outer_doc("/// a"), Some("/// a".to_string()));
+    assert_eq!(doc_text("/// a"), Some(" a".to_string()));
+    assert_eq!(doc_from_attr(r#"" a""#), Some(" a".to_string()));
+    assert_eq!(kind("b'a'"), LiteralKind::Byte);
+    assert!(is_lifetime("'a") && !is_lifetime("'a'"));
+    assert!(is_byte_string(r#"br"a""#) && is_raw(r#"br"a""#));
+    assert_eq!(value("b'a'"), Some(LitValue::Byte(b'a')));
+    assert_eq!(lenient(r#""a\qb""#).0, Some(LitValue::Str("a\u{FFFD}b".to_string())));
+    assert_eq!(full("b'a'").map(|p| p.into_value()), Some(LitValue::Byte(b'a')));
+}
@@ -1,16 +1,22 @@
 // NOTE: We need `DummyLiteral` to run our tests.
 #![cfg(all(feature = "dummy", test))]
 
-use {DummyLiteral, LiteralExt};
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+use {DummyLiteral, IntBase, IntLit, LiteralExt, LiteralError, LiteralKind, LitValue};
+use parse;
 
 #[test]
 fn ints() {
     macro_rules! test_int {
         ($i:tt) => {
-            test_int!($i, as_u8, as_i8, as_u16, as_i16, as_u32, as_i32, as_u64, as_i64);
+            test_int!($i, as_u8, as_i8, as_u16, as_i16, as_u32, as_i32, as_u64, as_i64,
+                      as_usize, as_isize);
         };
         ($i:tt, $($f:ident),*) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::Int);
             let asint = dl.parse_int().expect(&format!("Unable to parse {} as an integer", stringify!($i)));
             $(
                 assert_eq!(
@@ -50,6 +56,494 @@ fn ints() {
     test_int!(0x_7F__u8, as_u8);
     test_int!(0b__10__0_1i8, as_i8);
     test_int!(0o__7__________________3u32, as_u32);
+    test_int!(10usize, as_usize);
+    test_int!(10isize, as_isize);
+
+    #[cfg(feature = "i128")]
+    {
+        test_int!(5u128, as_u128);
+        test_int!(5i128, as_i128);
+        test_int!(170141183460469231731687303715884105727i128, as_i128);
+        test_int!(340282366920938463463374607431768211455u128, as_u128);
+    }
+}
+
+#[test]
+fn int_pointer_sized() {
+    let big = DummyLiteral("4294967296usize").parse_int().unwrap();
+    if cfg!(target_pointer_width = "32") {
+        assert_eq!(big.as_usize(), None);
+    } else {
+        assert_eq!(big.as_usize(), Some(4294967296));
+    }
+    assert_eq!(big.as_u64(), None);
+
+    let big = DummyLiteral("9223372036854775808isize").parse_int().unwrap();
+    assert_eq!(big.as_isize(), None);
+    assert_eq!(DummyLiteral("10u32").parse_int().unwrap().as_usize(), None);
+}
+
+#[test]
+fn signed_ints() {
+    let i = parse::signed_int("-128i8").unwrap();
+    assert!(i.is_negative());
+    assert_eq!(i.as_i8(), Some(-128));
+    assert_eq!(i.to_string(), "-128i8");
+    assert_eq!(parse::signed_int("128i8").unwrap().as_i8(), None);
+    assert_eq!(parse::signed_int("-129i8").unwrap().as_i8(), None);
+
+    let i = parse::signed_int("-0x7F").unwrap();
+    assert_eq!(i.as_i8(), Some(-127));
+    assert_eq!(i.as_i64(), Some(-127));
+    assert_eq!(i.as_u8(), None);
+    assert_eq!(i.as_u64(), None);
+    assert_eq!(parse::signed_int("-9223372036854775808").unwrap().as_i64(),
+               Some(i64::MIN));
+    assert_eq!(parse::signed_int("-0").unwrap().as_u8(), Some(0));
+
+    let i = parse::signed_int("+5u8").unwrap();
+    assert!(!i.is_negative());
+    assert_eq!(i.as_u8(), Some(5));
+    assert_eq!(parse::signed_int("5").unwrap().as_i32(), Some(5));
+    assert_eq!(parse::signed_int("--5"), None);
+
+    assert_eq!(DummyLiteral("-5").parse_int(), None);
+}
+
+#[test]
+fn int_raw_digits() {
+    let i = DummyLiteral("99_999_999_999_999_999_999_999_999_999_999_999_999u64")
+        .parse_int().unwrap();
+    assert_eq!(i.as_u64(), None);
+    assert_eq!(i.raw_digits(), "99999999999999999999999999999999999999");
+    assert_eq!(DummyLiteral("0x_dE_aDu32").parse_int().unwrap().raw_digits(), "dEaD");
+    assert_eq!(DummyLiteral("0b1010").parse_int().unwrap().raw_digits(), "1010");
+    assert_eq!(DummyLiteral("007").parse_int().unwrap().raw_digits(), "007");
+}
+
+#[test]
+fn int_separators() {
+    macro_rules! test_separators {
+        ($i:expr, $separators:expr) => {
+            let i = DummyLiteral($i).parse_int().unwrap();
+            let separators: &[usize] = &$separators;
+            assert_eq!(i.separators(), separators);
+            assert_eq!(i.has_separators(), !separators.is_empty());
+        }
+    }
+
+    test_separators!("1000000", []);
+    test_separators!("1_000_000", [1, 4]);
+    test_separators!("10_00_000", [2, 4]);
+    test_separators!("0x_FF", [0]);
+    test_separators!("1__0", [1, 1]);
+    test_separators!("1_u8", [1]);
+    test_separators!("0b1010_1010", [4]);
+    assert_eq!(parse::signed_int("-1_000").map(|i| i.separators().to_vec()), Some(vec![1]));
+    assert_eq!(parse::int_radix("z_z", 36).map(|i| i.separators().to_vec()), Some(vec![1]));
+    assert!(!IntLit::new(1000, None).has_separators());
+    // Separators are not part of the value.
+    assert_eq!(DummyLiteral("1_000").parse_int(), DummyLiteral("1000").parse_int());
+}
+
+#[test]
+fn int_radixes() {
+    use Radix;
+
+    macro_rules! test_radix {
+        ($i:expr, $radix:expr, $v:expr, $base:expr) => {
+            let i = parse::int_radix($i, $radix).unwrap();
+            assert_eq!(i.as_u64(), Some($v));
+            assert_eq!(i.base(), $radix);
+            assert_eq!(i.int_base(), $base);
+        }
+    }
+
+    test_radix!("zz", 36, 1295, IntBase::Other(Radix::new(36).unwrap()));
+    test_radix!("ZZ", 36, 1295, IntBase::Other(Radix::new(36).unwrap()));
+    test_radix!("1_0", 3, 3, IntBase::Other(Radix::new(3).unwrap()));
+    test_radix!("ff", 16, 255, IntBase::Hexadecimal);
+    test_radix!("777", 8, 511, IntBase::Octal);
+    test_radix!("1010", 2, 10, IntBase::Binary);
+    test_radix!("42", 10, 42, IntBase::Decimal);
+    assert_eq!(parse::int_radix("zz", 36).unwrap().to_string(), "zz");
+    assert_eq!(parse::int_radix("ff", 16).unwrap().to_string(), "0xff");
+
+    assert_eq!(parse::int_radix("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz", 36).map(|i| i.as_u64()),
+               Some(None));
+    assert_eq!(parse::int_radix("g", 16), None);
+    assert_eq!(parse::int_radix("12", 2), None);
+    assert_eq!(parse::int_radix("5u8", 10), None);
+    assert_eq!(parse::int_radix("0x5", 16), None);
+    assert_eq!(parse::int_radix("", 10), None);
+    assert_eq!(parse::int_radix("_", 10), None);
+    assert_eq!(parse::int_radix("0", 0), None);
+    assert_eq!(parse::int_radix("1", 1), None);
+    assert_eq!(parse::int_radix("1", 37), None);
+
+    assert_eq!(Radix::new(36).map(|r| r.get()), Some(36));
+    assert_eq!(Radix::new(16), None);
+    assert_eq!(Radix::new(2), None);
+    assert_eq!(Radix::new(1), None);
+    assert_eq!(Radix::new(37), None);
+}
+
+#[test]
+fn int_custom_suffixes() {
+    use IntConvError;
+
+    let units = ["px", "em", "u8"];
+    macro_rules! test_custom {
+        ($i:expr, $digits:expr, $suffix:expr, $offset:expr) => {
+            let i = parse::int_with_suffixes($i, &units).unwrap();
+            assert_eq!(i.raw_digits(), $digits);
+            assert_eq!(i.suffix(), $suffix);
+            assert_eq!(i.suffix_offset(), $offset);
+        }
+    }
+
+    test_custom!("5px", "5", "px", Some(1));
+    test_custom!("1_0em", "10", "em", Some(3));
+    test_custom!("0x1Fpx", "1F", "px", Some(4));
+    test_custom!("5", "5", "", None);
+    test_custom!("5u8", "5", "u8", Some(1));
+    // Custom suffixes are not Rust integer types, so can't be extracted as
+    // one, but Rust suffixes which are allowed are.
+    assert_eq!(parse::int_with_suffixes("5px", &units).and_then(|i| i.as_u64()), None);
+    assert_eq!(parse::int_with_suffixes("5", &units).and_then(|i| i.as_u64()), Some(5));
+    assert_eq!(parse::int_with_suffixes("5u8", &units).and_then(|i| i.as_u8()), Some(5));
+    assert_eq!(parse::int_with_suffixes("5px", &units).and_then(|i| i.suffix_ty()), None);
+    let err = parse::int_with_suffixes("5px", &units).unwrap().try_as_u64().unwrap_err();
+    assert_eq!(err, IntConvError::CustomSuffix { expected: "u64", found: "px".to_string() });
+    assert_eq!(err.to_string(), "custom suffix `px` on a value used as `u64`");
+
+    assert_eq!(parse::int_with_suffixes("5i32", &units), None);
+    assert_eq!(parse::int_with_suffixes("5pt", &units), None);
+    assert_eq!(parse::int_with_suffixes("5e3", &units), None);
+    assert_eq!(parse::int_with_suffixes("5.0px", &units), None);
+    // The default parser remains strict.
+    assert_eq!(parse::int("5px"), None);
+    assert_eq!(DummyLiteral("5em").parse_int(), None);
+}
+
+#[test]
+fn int_conversion_errors() {
+    use IntConvError;
+
+    let i = DummyLiteral("5u32").parse_int().unwrap();
+    assert_eq!(i.try_as_u32(), Ok(5));
+    let err = i.try_as_u8().unwrap_err();
+    assert_eq!(err, IntConvError::SuffixMismatch { expected: "u8", found: "u32" });
+    assert_eq!(err.to_string(), "suffix `u32` on a value used as `u8`");
+
+    let i = DummyLiteral("0x1_00").parse_int().unwrap();
+    assert_eq!(i.try_as_u16(), Ok(256));
+    let err = i.try_as_u8().unwrap_err();
+    assert_eq!(err, IntConvError::Overflow { value: "0x100".to_string(), ty: "u8" });
+    assert_eq!(err.to_string(), "value `0x100` is out of range for `u8`");
+
+    let i = DummyLiteral("99999999999999999999999999999999999999999i64").parse_int().unwrap();
+    assert_eq!(i.try_as_i64(), Err(IntConvError::Overflow {
+        value: "99999999999999999999999999999999999999999".to_string(),
+        ty: "i64",
+    }));
+    assert_eq!(parse::signed_int("-1").unwrap().try_as_usize(), Err(IntConvError::Overflow {
+        value: "-1".to_string(),
+        ty: "usize",
+    }));
+}
+
+#[test]
+fn int_declared_overflow() {
+    let overflows = |s: &str| parse::signed_int(s).unwrap().declared_overflow();
+    assert!(overflows("256u8"));
+    assert!(overflows("0x1_00u8"));
+    assert!(overflows("128i8"));
+    assert!(overflows("-1u32"));
+    assert!(overflows("-129i8"));
+    assert!(overflows("99999999999999999999999999999999999999999i64"));
+    assert!(!overflows("255u8"));
+    assert!(!overflows("-128i8"));
+    assert!(!overflows("0xFFFF_FFFFu32"));
+    assert!(!overflows("256"));
+    #[cfg(feature = "i128")]
+    assert!(overflows("340282366920938463463374607431768211456u128"));
+    #[cfg(feature = "i128")]
+    assert!(!overflows("340282366920938463463374607431768211455u128"));
+    assert!(!parse::int_with_suffixes("256px", &["px"]).unwrap().declared_overflow());
+
+    // Both ways of decoding any kind of literal reject them, as rustc does.
+    assert_eq!(DummyLiteral("256u8").value(), None);
+    assert_eq!(parse::value("0x1_00u8"), None);
+    assert!(DummyLiteral("256u8").parse_int().is_some());
+    assert!(DummyLiteral("255u8").value().is_some());
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn int_bigint() {
+    use num_bigint::BigInt;
+
+    let big = |s: &str| parse::signed_int(s).and_then(|i| i.as_bigint());
+    assert_eq!(big("5u8"), Some(BigInt::from(5)));
+    assert_eq!(big("-0x_FF"), Some(BigInt::from(-255)));
+    assert_eq!(big("0o777"), Some(BigInt::from(0o777)));
+    assert_eq!(big("0b1010_1010i64"), Some(BigInt::from(0b1010_1010)));
+    assert_eq!(big("340282366920938463463374607431768211456"),
+               Some(BigInt::from(1) << 128));
+    assert_eq!(big("0x1_0000_0000_0000_0000_0000_0000_0000_0000"),
+               Some(BigInt::from(1) << 128));
+    assert_eq!(parse::int_radix("zz", 36).and_then(|i| i.as_bigint()), Some(BigInt::from(1295)));
+}
+
+#[test]
+fn int_as_int_of() {
+    use IntSuffix;
+
+    let of = |s: &str, ty| parse::signed_int(s).unwrap().as_int_of(ty);
+    assert_eq!(of("255", IntSuffix::U8), Some(255));
+    assert_eq!(of("256", IntSuffix::U8), None);
+    assert_eq!(of("-128", IntSuffix::I8), Some(-128));
+    assert_eq!(of("-129", IntSuffix::I8), None);
+    assert_eq!(of("-1", IntSuffix::U64), None);
+    assert_eq!(of("-0", IntSuffix::U64), Some(0));
+    assert_eq!(of("5u8", IntSuffix::U8), Some(5));
+    assert_eq!(of("5u8", IntSuffix::U16), None);
+    assert_eq!(of("0xFFFF_FFFF", IntSuffix::U32), Some(0xFFFF_FFFF));
+    assert_eq!(of("0xFFFF_FFFF", IntSuffix::I32), None);
+    assert_eq!(of("5", IntSuffix::Isize), Some(5));
+    assert_eq!(of("-9223372036854775808", IntSuffix::I64), Some(i64::MIN as i128));
+    #[cfg(feature = "i128")]
+    {
+        let max = "170141183460469231731687303715884105727";
+        assert_eq!(of(max, IntSuffix::U128), Some(i128::MAX));
+        assert_eq!(of("170141183460469231731687303715884105728", IntSuffix::U128), None);
+        assert_eq!(of("-170141183460469231731687303715884105728", IntSuffix::I128),
+                   Some(i128::MIN));
+    }
+    #[cfg(not(feature = "i128"))]
+    assert_eq!(of("18446744073709551616", IntSuffix::U128), None);
+}
+
+#[test]
+fn int_lenient_signs() {
+    assert_eq!(parse::int("+5"), None);
+    assert_eq!(parse::int("-5"), None);
+    assert_eq!(DummyLiteral("+5").parse_int(), None);
+
+    let lit = parse::int_lenient("+5").unwrap();
+    assert_eq!((lit.as_u8(), lit.is_negative()), (Some(5), false));
+    let lit = parse::int_lenient("-0XFFi32").unwrap();
+    assert_eq!((lit.as_i32(), lit.is_negative()), (Some(-255), true));
+    assert_eq!(lit.suffix_offset(), Some(5));
+    assert_eq!(parse::int_lenient("+0B101u8").and_then(|i| i.as_u8()), Some(5));
+    assert_eq!(parse::int_lenient("-5u8"), parse::signed_int("-5u8"));
+    assert_eq!(parse::int_lenient("-5u8").and_then(|i| i.as_u8()), None);
+    assert_eq!(parse::int_lenient("- 5"), None);
+}
+
+#[test]
+fn int_eq_primitives() {
+    let int = |s: &str| parse::signed_int(s).unwrap();
+    assert!(int("0") == 0u64);
+    assert!(int("5u8") == 5u8 && int("5u8") != 5u64 && int("5u8") != 5i8);
+    assert!(int("5") == 5u8 && int("5") == 5i64 && int("5") == 5usize);
+    assert!(int("0xFF_u8") == 255u8 && int("0xFF") == 255i32);
+    assert!(int("-5i32") == -5i32 && int("-5i32") != -5i64 && int("-5") == -5isize);
+    assert!(int("-5i32") != 5u32 && int("-5") != 5i32);
+    assert!(int("5") != -5i32 && int("5") != 6u8);
+    assert!(int("-0") == 0u8 && int("-0") == 0i8);
+    assert!(int("128") == 128u8 && int("128") != -128i8);
+    assert!(int("-128") == i8::MIN && int("-9223372036854775808") == i64::MIN);
+    assert!(int("18446744073709551615") == u64::MAX);
+    assert!(int("99999999999999999999999999999999999999999") != u64::MAX);
+    let units = ["px"];
+    assert!(parse::int_with_suffixes("5px", &units).unwrap() != 5u8);
+    #[cfg(feature = "i128")]
+    {
+        assert!(int("340282366920938463463374607431768211455") == u128::MAX);
+        assert!(int("-170141183460469231731687303715884105728") == i128::MIN);
+        assert!(int("18446744073709551616") != 0u64);
+    }
+}
+
+#[test]
+fn int_try_from() {
+    use std::convert::{TryFrom, TryInto};
+    use IntConvError;
+
+    let i = DummyLiteral("200").parse_int().unwrap();
+    assert_eq!(u8::try_from(&i), Ok(200));
+    assert_eq!(i8::try_from(&i), Err(IntConvError::Overflow {
+        value: "200".to_string(),
+        ty: "i8",
+    }));
+    let n: Result<u64, _> = i.try_into();
+    assert_eq!(n, Ok(200));
+
+    let i = DummyLiteral("5u32").parse_int().unwrap();
+    let n: Result<u32, _> = i.clone().try_into();
+    assert_eq!(n, Ok(5));
+    assert_eq!(usize::try_from(i), Err(IntConvError::SuffixMismatch {
+        expected: "usize",
+        found: "u32",
+    }));
+
+    let i = parse::signed_int("-128").unwrap();
+    assert_eq!(i8::try_from(&i), Ok(-128));
+    assert!(u8::try_from(&i).is_err());
+}
+
+#[test]
+fn int_ordering() {
+    let int = |s: &str| s.parse::<IntLit>().unwrap();
+
+    assert!(int("10") < int("20"));
+    assert!(int("0x10") > int("10"));
+    assert!(int("99999999999999999999999999999999999999999") > int("0xFFFF_FFFF_FFFF_FFFF"));
+    assert!(parse::signed_int("-5").unwrap() < int("0"));
+    assert!(parse::signed_int("-5").unwrap() < parse::signed_int("-4").unwrap());
+    assert!(int("5") < int("5u8"));
+
+    let mut ints = [int("3"), int("1u8"), int("0x2"), int("1"), int("0b11")];
+    ints.sort();
+    let sorted: Vec<String> = ints.iter().map(|i| i.to_string()).collect();
+    assert_eq!(sorted, ["1", "1u8", "0x2", "0b11", "3"]);
+}
+
+#[test]
+fn int_suffixes() {
+    use IntSuffix;
+
+    macro_rules! test_suffix {
+        ($i:tt, $suffix:expr) => {
+            let asint = DummyLiteral(stringify!($i)).parse_int().unwrap();
+            let suffix: Option<IntSuffix> = $suffix;
+            assert_eq!(asint.suffix_ty(), suffix);
+            assert_eq!(asint.suffix_ty().map_or("", |s| s.as_str()), asint.suffix());
+            assert_eq!(asint.suffix_offset(),
+                       suffix.map(|_| stringify!($i).len() - asint.suffix().len()));
+        }
+    }
+
+    test_suffix!(5, None);
+    test_suffix!(5u8, Some(IntSuffix::U8));
+    test_suffix!(5i16, Some(IntSuffix::I16));
+    test_suffix!(0xFFu32, Some(IntSuffix::U32));
+    test_suffix!(5i64, Some(IntSuffix::I64));
+    test_suffix!(5usize, Some(IntSuffix::Usize));
+    test_suffix!(5isize, Some(IntSuffix::Isize));
+    #[cfg(feature = "i128")]
+    {
+        test_suffix!(5u128, Some(IntSuffix::U128));
+        test_suffix!(5i128, Some(IntSuffix::I128));
+    }
+}
+
+#[cfg(feature = "i128")]
+#[test]
+fn int_any_suffix() {
+    macro_rules! test_any {
+        ($i:expr, $signed:expr, $unsigned:expr) => {
+            let i = parse::signed_int($i).unwrap();
+            assert_eq!(i.as_i128_any(), $signed);
+            assert_eq!(i.as_u128_any(), $unsigned);
+        }
+    }
+
+    test_any!("5u8", Some(5), Some(5));
+    test_any!("5i8", Some(5), Some(5));
+    test_any!("5", Some(5), Some(5));
+    test_any!("0xFFusize", Some(255), Some(255));
+    test_any!("-5u8", Some(-5), None);
+    test_any!("-0", Some(0), Some(0));
+    test_any!("170141183460469231731687303715884105727u8",
+              Some(i128::MAX), Some(i128::MAX as u128));
+    test_any!("170141183460469231731687303715884105728", None, Some(1 << 127));
+    test_any!("-170141183460469231731687303715884105728", Some(i128::MIN), None);
+    test_any!("-170141183460469231731687303715884105729", None, None);
+    test_any!("340282366920938463463374607431768211455i8", None, Some(u128::MAX));
+    test_any!("340282366920938463463374607431768211456", None, None);
+
+    // The suffixed conversions still require the suffix to match.
+    assert_eq!(parse::int("5u8").and_then(|i| i.as_i128()), None);
+}
+
+#[test]
+fn int_new() {
+    use IntSuffix;
+
+    assert_eq!(IntLit::new(5, None), DummyLiteral("5").parse_int().unwrap());
+    assert_eq!(IntLit::new(255, Some(IntSuffix::U8)),
+               DummyLiteral("255u8").parse_int().unwrap());
+    assert_eq!(IntLit::new(255, Some(IntSuffix::U8)).to_string(), "255u8");
+    assert_eq!(IntLit::new(255, Some(IntSuffix::U8)).suffix_offset(), Some(3));
+    assert_eq!(IntLit::new(256, Some(IntSuffix::U8)).as_u8(), None);
+    assert_eq!(IntLit::new(u128::MAX, None).raw_digits(),
+               "340282366920938463463374607431768211455");
+    #[cfg(not(feature = "i128"))]
+    assert_eq!(IntLit::new(u128::MAX, None).as_u64(), None);
+}
+
+#[test]
+fn int_fits() {
+    let lit = DummyLiteral("255").parse_int().unwrap();
+    assert!(lit.fits_u8());
+    assert!(!lit.fits_i8());
+    assert!(lit.fits_i16());
+    assert!(lit.fits_usize());
+
+    let lit = DummyLiteral("255u16").parse_int().unwrap();
+    assert!(!lit.fits_u8());
+    assert!(lit.fits_u16());
+    assert!(!lit.fits_u32());
+
+    let lit = parse::signed_int("-128").unwrap();
+    assert!(lit.fits_i8());
+    assert!(!lit.fits_u8());
+    assert!(!parse::signed_int("-129").unwrap().fits_i8());
+
+    let lit = DummyLiteral("99999999999999999999999999999999999999999").parse_int().unwrap();
+    assert!(!lit.fits_u64());
+    assert!(!lit.fits_isize());
+}
+
+#[test]
+fn int_bases() {
+    macro_rules! test_base {
+        ($i:tt, $base:expr, $radix:expr) => {
+            let dl = DummyLiteral(stringify!($i));
+            let asint = dl.parse_int().expect(&format!("Unable to parse {} as an integer", stringify!($i)));
+            assert_eq!(asint.int_base(), $base);
+            assert_eq!(asint.base(), $radix);
+        }
+    }
+
+    test_base!(0b1010, IntBase::Binary, 2);
+    test_base!(0o17, IntBase::Octal, 8);
+    test_base!(255, IntBase::Decimal, 10);
+    test_base!(0xFF, IntBase::Hexadecimal, 16);
+    test_base!(0x_FFu8, IntBase::Hexadecimal, 16);
+}
+
+#[test]
+fn int_display() {
+    macro_rules! test_int_display {
+        ($i:expr, $s:expr) => {
+            let asint = DummyLiteral($i).parse_int().unwrap();
+            assert_eq!(asint.to_string(), $s);
+            assert_eq!(DummyLiteral(asint.to_string()).parse_int(), Some(asint));
+        }
+    }
+
+    test_int_display!("5", "5");
+    test_int_display!("5u8", "5u8");
+    test_int_display!("1_000_000i64", "1000000i64");
+    test_int_display!("0xFF_FFu32", "0xFFFFu32");
+    test_int_display!("99_999_999_999_999_999_999_999_999_999_999_999_999",
+                      "99999999999999999999999999999999999999");
+    test_int_display!("0o17", "0o17");
+    test_int_display!("0b1010usize", "0b1010usize");
 }
 
 #[test]
@@ -60,6 +554,7 @@ fn floats() {
         };
         ($i:tt, $($f:ident),*) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::Float);
             let asfloat = dl.parse_float()
                 .expect(&format!("Unable to parse {} as a float", stringify!($i)));
             $(
@@ -86,11 +581,388 @@ fn floats() {
     test_float!(1.03e+23);
 }
 
+#[test]
+fn float_other_bases() {
+    for s in &["0x1.8p3", "0x1p4", "0xAf32", "0x1.5", "0o7.0", "0b1.0", "0b1f32", "0o7e3"] {
+        let dl = DummyLiteral(*s);
+        assert_eq!(dl.parse_float(), None, "{}", s);
+        assert_eq!(dl.parse_float_forgiving(), None, "{}", s);
+    }
+    assert_eq!(DummyLiteral("0xAf32").parse_int().and_then(|i| i.as_u32()), Some(0xAF32));
+    assert_eq!(DummyLiteral("0xAf32").kind(), LiteralKind::Int);
+}
+
+#[test]
+fn float_special_values() {
+    for &val in &[f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+        let dl = DummyLiteral(val);
+        assert_eq!(dl.parse_float(), None, "{}", val);
+        assert_eq!(dl.parse_float_forgiving(), None, "{}", val);
+        assert_eq!(dl.kind(), LiteralKind::Unknown, "{}", val);
+        let special = parse::float_special(&val.to_string());
+        assert!(special.is_some_and(|s| s == val || (s.is_nan() && val.is_nan())), "{}", val);
+    }
+    for s in &["inf", "-inf", "NaN"] {
+        assert_eq!(parse::float(s), None, "{}", s);
+    }
+    for s in &["Inf", "nan", "+inf", "infinity", "inff32", "1.0", ""] {
+        assert_eq!(parse::float_special(s), None, "{}", s);
+    }
+}
+
+#[test]
+fn float_trailing_garbage() {
+    for s in &["0.0x", "0.f32x", "1e3garbage", "1.0f32x", "1e3f", "0.0b1", "1.5e", "1e+"] {
+        let dl = DummyLiteral(*s);
+        assert_eq!(dl.parse_float(), None, "{}", s);
+        assert_eq!(dl.parse_float_forgiving(), None, "{}", s);
+        assert_eq!(parse::float(s), None, "{}", s);
+    }
+}
+
+#[test]
+fn float_display() {
+    macro_rules! test_float_display {
+        ($i:expr, $s:expr) => {
+            let asfloat = DummyLiteral($i).parse_float().unwrap();
+            assert_eq!(asfloat.to_string(), $s);
+            assert_eq!(DummyLiteral(asfloat.to_string()).parse_float(), Some(asfloat));
+        }
+    }
+
+    test_float_display!("5.5", "5.5");
+    test_float_display!("5.", "5.0");
+    test_float_display!("5f32", "5.0f32");
+    test_float_display!("1_0.2_5f64", "10.25f64");
+    test_float_display!("1e10", "10000000000.0");
+    test_float_display!("1.5E300", "1.5e300");
+    test_float_display!("1e-7", "1e-7");
+}
+
+#[test]
+fn float_suffixes() {
+    use FloatSuffix;
+
+    assert_eq!(DummyLiteral("5.0").parse_float().unwrap().suffix_ty(), None);
+    assert_eq!(DummyLiteral("5f32").parse_float().unwrap().suffix_ty(), Some(FloatSuffix::F32));
+    assert_eq!(DummyLiteral("5e3f64").parse_float().unwrap().suffix_ty(), Some(FloatSuffix::F64));
+    assert_eq!(FloatSuffix::F32.as_str(), "f32");
+
+    assert_eq!(DummyLiteral("5.0").parse_float().unwrap().suffix_offset(), None);
+    assert_eq!(DummyLiteral("5_0f32").parse_float().unwrap().suffix_offset(), Some(3));
+    assert_eq!(DummyLiteral("1.5e1_0f64").parse_float().unwrap().suffix_offset(), Some(7));
+}
+
+#[test]
+fn float_new() {
+    use {FloatLit, FloatSuffix};
+
+    assert_eq!(FloatLit::new(1.5, None), DummyLiteral("1.5").parse_float().unwrap());
+    assert_eq!(FloatLit::new(1.5, Some(FloatSuffix::F32)).to_string(), "1.5f32");
+    assert_eq!(FloatLit::new(1.5, Some(FloatSuffix::F32)).as_f64(), None);
+}
+
+#[test]
+fn float_reprs() {
+    macro_rules! test_repr {
+        ($i:expr, $mantissa:expr, $dot:expr, $marker:expr, $sign:expr, $exp:expr, $text:expr) => {
+            let repr = *DummyLiteral($i).parse_float().unwrap().repr();
+            assert_eq!(repr.mantissa(), $mantissa);
+            assert_eq!(repr.has_dot(), $dot);
+            assert_eq!(repr.exp_marker(), $marker);
+            assert_eq!(repr.exp_sign(), $sign);
+            assert_eq!(repr.exp_digits(), $exp);
+            assert_eq!(repr.to_string(), $text);
+        }
+    }
+
+    test_repr!("1.03e+23", "1.03", true, Some('e'), Some('+'), "23", "1.03e+23");
+    test_repr!("1.03e23", "1.03", true, Some('e'), None, "23", "1.03e23");
+    test_repr!("1.03E23", "1.03", true, Some('E'), None, "23", "1.03E23");
+    test_repr!("1e-2_3f64", "1", false, Some('e'), Some('-'), "2_3", "1e-2_3");
+    test_repr!("1_0.5", "1_0.5", true, None, None, "", "1_0.5");
+    test_repr!("1.", "1.", true, None, None, "", "1.");
+    test_repr!("5f32", "5", false, None, None, "", "5");
+
+    use FloatLit;
+    assert_eq!(FloatLit::new(1.5, None).repr().to_string(), "1.5");
+    assert_eq!(FloatLit::new(1e100, None).repr().exp_digits(), "100");
+    // The way the digits were written is not part of the value.
+    assert_eq!(DummyLiteral("1e2").parse_float(), DummyLiteral("100.0").parse_float());
+
+    // Digits which are too long to keep are replaced with the shortest form,
+    // but are still used by `is_exact` and `decompose`.
+    let long = format!("0.{}1", "0".repeat(70));
+    let lit = DummyLiteral(&long).parse_float().unwrap();
+    assert_eq!(lit.repr().to_string(), "1e-71");
+    assert_eq!(lit.decompose(), Some((1, -71)));
+    assert!(!lit.is_exact());
+    let long = format!("{}.5", "0".repeat(70));
+    let lit = DummyLiteral(&long).parse_float().unwrap();
+    assert_eq!(lit.repr().to_string(), "0.5");
+    assert!(lit.is_exact());
+    // `FloatLit` is `Copy`.
+    let copy = lit;
+    assert_eq!(copy, lit);
+}
+
+#[test]
+fn float_exact() {
+    macro_rules! test_exact {
+        ($i:expr, $exact:expr) => {
+            assert_eq!(DummyLiteral($i).parse_float().unwrap().is_exact(), $exact, "{}", $i);
+        }
+    }
+
+    test_exact!("0.5", true);
+    test_exact!("0.5f32", true);
+    test_exact!("1e10", true);
+    test_exact!("1e10f32", true);
+    test_exact!("1_0.2_5e1", true);
+    test_exact!("0.0", true);
+    test_exact!("0e999999999999999999999", true);
+    test_exact!("00.1250", true);
+    test_exact!("125e-3", true);
+    test_exact!("16777216f32", true);
+    test_exact!("16777217.0", true);
+    test_exact!("9007199254740992.0", true);
+    test_exact!("0.1", false);
+    test_exact!("0.1f32", false);
+    test_exact!("0.3f64", false);
+    test_exact!("16777217f32", false);
+    test_exact!("9007199254740993.0", false);
+    test_exact!("1e23", false);
+    test_exact!("1e400", false);
+    test_exact!("1e-400", false);
+    test_exact!("1e99999999999999999999", false);
+
+    use FloatLit;
+    assert!(FloatLit::new(0.5, None).is_exact());
+    assert!(!FloatLit::new(0.1, None).is_exact());
+}
+
+#[test]
+fn literal_hashes() {
+    use std::collections::HashSet;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use {FloatLit, FloatSuffix};
+
+    fn hash<T: Hash>(t: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        t.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Equal integers hash equally, even if their suffixes were at different
+    // positions in the source.
+    let a = DummyLiteral("0x_FFu8").parse_int().unwrap();
+    let b = DummyLiteral("0xFFu8").parse_int().unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    let set: HashSet<IntLit> = ["5", "5", "5u8", "0x5", "5_u8"].iter()
+        .map(|i| DummyLiteral(i).parse_int().unwrap())
+        .collect();
+    assert_eq!(set.len(), 3);
+
+    let a = DummyLiteral("1e2f32").parse_float().unwrap();
+    let b = DummyLiteral("10_0.0f32").parse_float().unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    assert_eq!(hash(&FloatLit::new(0.0, None)), hash(&FloatLit::new(-0.0, None)));
+    assert!(hash(&FloatLit::new(1.0, None)) != hash(&FloatLit::new(1.0, Some(FloatSuffix::F32))));
+    let nan = FloatLit::new(f64::NAN, None);
+    let copy = nan;
+    assert!(nan != copy);
+    assert_eq!(hash(&nan), hash(&copy));
+}
+
+#[test]
+fn number_f64() {
+    let num = |s: &str| DummyLiteral(s).parse_number_f64();
+    assert_eq!(num("5"), Some(5.0));
+    assert_eq!(num("5u8"), Some(5.0));
+    assert_eq!(num("5.0"), Some(5.0));
+    assert_eq!(num("5f32"), Some(5.0));
+    assert_eq!(num("2.5e3f64"), Some(2500.0));
+    assert_eq!(num("0xFF_u16"), Some(255.0));
+    assert_eq!(num("0b101"), Some(5.0));
+    assert_eq!(num("1_000"), Some(1000.0));
+    assert_eq!(num("18446744073709551615u64"), Some(18446744073709551615u64 as f64));
+    // Decimal integers are rounded, even if they overflow every integer type.
+    assert_eq!(num("1000000000000000000000000000000000000000"), Some(1e39));
+    assert_eq!(num("9007199254740993"), Some(9007199254740992.0));
+    assert_eq!(parse::number_f64("-5i8"), None);
+    assert_eq!(num("'a'"), None);
+    assert_eq!(num("\"5\""), None);
+}
+
+#[test]
+fn suffixed() {
+    for s in &["5u8", "0xFFi64", "1_000usize", "1.0f32", "1e10f64", "5f32"] {
+        assert!(DummyLiteral(*s).is_suffixed(), "{}", s);
+    }
+    for s in &["5", "0xFF", "1.0", "1e10", "\"u8\"", "'a'", "b'a'", "foo"] {
+        assert!(!DummyLiteral(*s).is_suffixed(), "{}", s);
+    }
+    // Custom suffixes are not integer literals at all.
+    assert!(!DummyLiteral("5px").is_suffixed());
+}
+
+#[test]
+fn numeric_bridging() {
+    let int = |s: &str| parse::signed_int(s).unwrap();
+    let float = |s: &str| parse::float(s).unwrap();
+
+    assert_eq!(float("5.0").to_int_exact(), Some(5));
+    assert_eq!(float("1e3f32").to_int_exact(), Some(1000));
+    assert_eq!(float("2.5e1").to_int_exact(), Some(25));
+    assert_eq!(float("5.5").to_int_exact(), None);
+    assert_eq!(float("1e-3").to_int_exact(), None);
+    assert_eq!(float("1e30").to_int_exact(), Some(1e30 as i128));
+    assert_eq!(float("1.7014118346046923e38").to_int_exact(), None);
+    assert_eq!(float("1e400").to_int_exact(), None);
+    // The `f32` value of `16777217f32` is `16777216`.
+    assert_eq!(float("16777217f32").to_int_exact(), Some(16777216));
+
+    assert_eq!(int("5u8").to_float_lossy(), Some(5.0));
+    assert_eq!(int("-0x10").to_float_lossy(), Some(-16.0));
+    assert_eq!(int("9007199254740993").to_float_lossy(), Some(9007199254740992.0));
+    assert_eq!(int("9007199254740993").to_float_exact(), None);
+    assert_eq!(int("9007199254740992").to_float_exact(), Some(9007199254740992.0));
+    assert_eq!(int("1152921504606846976").to_float_exact(), Some(1152921504606846976.0));
+    assert_eq!(int("-1_000").to_float_exact(), Some(-1000.0));
+    assert_eq!(int("0x20_0000_0000_0001").to_float_exact(), None);
+    assert_eq!(int("0x20_0000_0000_0000").to_float_exact(), Some(9007199254740992.0));
+    assert_eq!(int("0xFFFF_FFFF_FFFF_FFFF").to_float_exact(), None);
+    // 2^70 is exact, even though it overflows a `u64`.
+    assert_eq!(int("1180591620717411303424").to_float_exact(), Some(1180591620717411303424.0));
+    assert_eq!(int("1180591620717411303425").to_float_exact(), None);
+}
+
+#[test]
+fn float_decompose() {
+    macro_rules! test_decompose {
+        ($i:expr, $parts:expr) => {
+            assert_eq!(DummyLiteral($i).parse_float().unwrap().decompose(), $parts, "{}", $i);
+        }
+    }
+
+    test_decompose!("1.25e3", Some((125, 1)));
+    test_decompose!("1.25", Some((125, -2)));
+    test_decompose!("1.50", Some((150, -2)));
+    test_decompose!("1_0.2_5e1_0", Some((1025, 8)));
+    test_decompose!("0.001", Some((1, -3)));
+    test_decompose!("1e-3f32", Some((1, -3)));
+    test_decompose!("1E+3", Some((1, 3)));
+    test_decompose!("1.", Some((1, 0)));
+    test_decompose!("0.0", Some((0, -1)));
+    test_decompose!("0.1f32", Some((1, -1)));
+    test_decompose!("5f64", Some((5, 0)));
+    test_decompose!("170141183460469231731687303715884105727e0", Some((i128::MAX, 0)));
+    test_decompose!("170141183460469231731687303715884105728e0", None);
+    // Every digit is part of the mantissa, including trailing zeros.
+    test_decompose!("170141183460469231731687303715884105727.0", None);
+    test_decompose!("1e2147483647", Some((1, i32::MAX)));
+    test_decompose!("1e2147483648", None);
+    test_decompose!("0.1e-2147483648", None);
+
+    use FloatLit;
+    assert_eq!(FloatLit::new(1e100, None).decompose(), Some((1, 100)));
+    assert_eq!(FloatLit::new(2.5, None).decompose(), Some((25, -1)));
+}
+
+#[test]
+fn float_forgiving() {
+    assert_eq!(DummyLiteral("1").parse_float(), None);
+    assert_eq!(DummyLiteral("1").parse_float_forgiving().and_then(|f| f.as_f64()), Some(1.0));
+    assert_eq!(DummyLiteral("1_000").parse_float_forgiving().and_then(|f| f.as_f32()),
+               Some(1000.0));
+    assert_eq!(DummyLiteral("1").parse_float_forgiving().map(|f| f.repr().to_string()),
+               Some("1".to_string()));
+    assert_eq!(DummyLiteral("1.5f32").parse_float_forgiving().and_then(|f| f.as_f32()),
+               Some(1.5));
+    assert_eq!(DummyLiteral("1f64").parse_float_forgiving().and_then(|f| f.as_f64()),
+               Some(1.0));
+    assert_eq!(DummyLiteral("1u8").parse_float_forgiving(), None);
+    assert_eq!(DummyLiteral("0x10").parse_float_forgiving(), None);
+    assert_eq!(DummyLiteral("1px").parse_float_forgiving(), None);
+    assert_eq!(DummyLiteral("\"1\"").parse_float_forgiving(), None);
+}
+
+#[test]
+fn token_whitespace() {
+    // Some backends render literal tokens with surrounding whitespace.
+    assert_eq!(DummyLiteral(" 5u8\n").parse_int().and_then(|i| i.as_u8()), Some(5));
+    assert_eq!(DummyLiteral("\t1.5 ").parse_float().and_then(|f| f.as_f64()), Some(1.5));
+    assert_eq!(DummyLiteral(" \" a \" ").parse_string(), Some(" a ".to_string()));
+    assert_eq!(DummyLiteral(" 'a' ").kind(), LiteralKind::Char);
+    assert_eq!(DummyLiteral(" b'a' ").raw_text(), "b'a'");
+    assert_eq!(DummyLiteral(" b\"a\" ").bytes_iter().map(|i| i.collect::<Vec<_>>()),
+               Some(b"a".to_vec()));
+    // Whitespace is still significant in the text given to `parse`.
+    assert_eq!(parse::int(" 5"), None);
+}
+
+#[test]
+fn int_float_precedence() {
+    macro_rules! test_precedence {
+        ($i:expr, $kind:expr, $int:expr, $float:expr) => {
+            let dl = DummyLiteral($i);
+            assert_eq!(dl.kind(), $kind);
+            assert_eq!(dl.parse_int().is_some(), $int);
+            assert_eq!(dl.parse_float().is_some(), $float);
+            match dl.value() {
+                Some(LitValue::Int(_)) => assert_eq!($kind, LiteralKind::Int),
+                Some(LitValue::Float(_)) => assert_eq!($kind, LiteralKind::Float),
+                x => panic!("unexpected value {:?}", x),
+            }
+        }
+    }
+
+    test_precedence!("5f32", LiteralKind::Float, false, true);
+    test_precedence!("5f64", LiteralKind::Float, false, true);
+    test_precedence!("5i32", LiteralKind::Int, true, false);
+    test_precedence!("5u8", LiteralKind::Int, true, false);
+    // NOTE: An unsuffixed integer is never a float, as Rust won't infer a
+    // float type for it.
+    test_precedence!("5", LiteralKind::Int, true, false);
+    test_precedence!("5_000", LiteralKind::Int, true, false);
+    test_precedence!("5.", LiteralKind::Float, false, true);
+    test_precedence!("5e3", LiteralKind::Float, false, true);
+    test_precedence!("5E3", LiteralKind::Float, false, true);
+    // NOTE: In hex, `f32` are digits rather than a suffix.
+    test_precedence!("0x5f32", LiteralKind::Int, true, false);
+}
+
+#[test]
+fn float_overflow() {
+    use FloatConvError;
+
+    let f = DummyLiteral("1e400").parse_float().unwrap();
+    assert!(!f.is_finite());
+    assert_eq!(f.try_as_f64(), Err(FloatConvError::Overflow { ty: "f64" }));
+    assert_eq!(f.try_as_f32(), Err(FloatConvError::Overflow { ty: "f32" }));
+
+    let f = DummyLiteral("1e300").parse_float().unwrap();
+    assert!(f.is_finite());
+    assert_eq!(f.try_as_f64(), Ok(1e300));
+    assert_eq!(f.try_as_f32(), Err(FloatConvError::Overflow { ty: "f32" }));
+    assert_eq!(f.as_f32(), Some(f32::INFINITY));
+
+    let f = DummyLiteral("1.5f32").parse_float().unwrap();
+    assert_eq!(f.try_as_f32(), Ok(1.5));
+    let err = f.try_as_f64().unwrap_err();
+    assert_eq!(err, FloatConvError::SuffixMismatch { expected: "f64", found: "f32" });
+    assert_eq!(err.to_string(), "suffix `f32` on a value used as `f64`");
+}
+
 #[test]
 fn chars() {
     macro_rules! test_char {
         ($i:tt) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::Char);
             assert_eq!(dl.parse_char(), Some($i));
             assert_eq!(dl.parse_int(), None);
             assert_eq!(dl.parse_float(), None);
@@ -110,6 +982,89 @@ fn chars() {
     test_char!('\'');
     test_char!('"');
     test_char!('\u{1F415}');
+    test_char!('\u{1_F4_15}');
+    test_char!('\x7F');
+}
+
+#[test]
+fn char_lits() {
+    use EscapeKind;
+
+    macro_rules! test_char_lit {
+        ($i:expr, $c:expr, $e:expr) => {
+            let lit = DummyLiteral($i).parse_char_lit().unwrap();
+            assert_eq!(lit.value(), $c);
+            assert_eq!(lit.escape(), $e);
+        }
+    }
+
+    test_char_lit!("'A'", 'A', EscapeKind::None);
+    test_char_lit!("'🐕'", '🐕', EscapeKind::None);
+    test_char_lit!(r"'\''", '\'', EscapeKind::Ascii);
+    test_char_lit!(r"'\\'", '\\', EscapeKind::Ascii);
+    test_char_lit!(r"'\x41'", 'A', EscapeKind::Hex);
+    test_char_lit!(r"'\u{41}'", 'A', EscapeKind::Unicode);
+    test_char_lit!(r"'\u{1_F4_15}'", '🐕', EscapeKind::Unicode);
+    test_char_lit!(r"'\n'", '\n', EscapeKind::Named);
+    test_char_lit!(r"'\0'", '\0', EscapeKind::Named);
+    assert_eq!(DummyLiteral(r"'\q'").parse_char_lit(), None);
+    assert_eq!(DummyLiteral(r"'\u{_1F415}'").parse_char_lit(), None);
+    assert_eq!(DummyLiteral(r#""a\u{1_F4_15}""#).parse_string(), Some("a🐕".to_string()));
+    assert_eq!(DummyLiteral("\"a\"").parse_char_lit(), None);
+}
+
+#[test]
+fn char_errors() {
+    macro_rules! test_char_err {
+        ($i:expr, $e:expr) => {
+            let dl = DummyLiteral($i);
+            assert_eq!(dl.try_parse_char(), Err($e));
+            assert_eq!(dl.parse_char(), None);
+        }
+    }
+
+    test_char_err!(r"'\q'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\x'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\x80'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\xFF'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u{D800}'", LiteralError::InvalidCodePoint { offset: 1 });
+    test_char_err!(r"'\u{110000}'", LiteralError::InvalidCodePoint { offset: 1 });
+    test_char_err!(r"'\u{DFFF}'", LiteralError::InvalidCodePoint { offset: 1 });
+    test_char_err!(r"'\u{}'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u{1234567}'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u{0000041}'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u{41'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u{41", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!(r"'\u41'", LiteralError::InvalidEscape { offset: 1 });
+    test_char_err!("'ab'", LiteralError::MultipleCharacters { offset: 2 });
+    test_char_err!("'a'b", LiteralError::InvalidSuffix { offset: 3 });
+    test_char_err!("'a", LiteralError::UnterminatedLiteral { offset: 2 });
+    test_char_err!("'", LiteralError::UnterminatedLiteral { offset: 1 });
+    test_char_err!(r"'\", LiteralError::UnterminatedLiteral { offset: 2 });
+    test_char_err!("\"a\"", LiteralError::NotThisKind);
+}
+
+#[test]
+fn non_literal_tokens() {
+    // Lifetimes and raw identifiers are not literals, but may be fed to the
+    // parser by stringifying arbitrary tokens.
+    for s in &["'a", "'static", "'_", "r#foo", "r#match", "foo"] {
+        let dl = DummyLiteral(*s);
+        assert_eq!(dl.value(), None, "{}", s);
+        assert_eq!(dl.parse_char(), None, "{}", s);
+        assert_eq!(dl.parse_byte(), None, "{}", s);
+        assert_eq!(dl.parse_string(), None, "{}", s);
+        assert_eq!(dl.parse_int(), None, "{}", s);
+        assert_eq!(dl.parse_float(), None, "{}", s);
+    }
+    assert!(DummyLiteral("'static").try_parse_char().is_err());
+
+    for s in &["'a", "'static", "'_", "'_foo", "'a1", "'\u{e9}t\u{e9}"] {
+        assert!(DummyLiteral(*s).is_lifetime(), "{}", s);
+    }
+    for s in &["'a'", "'", "''", "'1", "'a b", "'a-", "'\\n", "a", "r#foo", "\"a\""] {
+        assert!(!DummyLiteral(*s).is_lifetime(), "{}", s);
+    }
 }
 
 #[test]
@@ -117,6 +1072,7 @@ fn byte() {
     macro_rules! test_byte {
         ($i:tt) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::Byte);
             assert_eq!(dl.parse_byte(), Some($i));
             assert_eq!(dl.parse_int(), None);
             assert_eq!(dl.parse_float(), None);
@@ -134,6 +1090,43 @@ fn byte() {
     test_byte!(b'\t');
     test_byte!(b'\'');
     test_byte!(b'"');
+    test_byte!(b'\x7F');
+    test_byte!(b'\x80');
+    test_byte!(b'\xFF');
+}
+
+#[test]
+fn byte_lits() {
+    use EscapeKind;
+
+    macro_rules! test_byte_lit {
+        ($i:expr, $b:expr, $e:expr) => {
+            let lit = DummyLiteral($i).parse_byte_lit().unwrap();
+            assert_eq!(lit.value(), $b);
+            assert_eq!(lit.escape(), $e);
+        }
+    }
+
+    test_byte_lit!("b'A'", b'A', EscapeKind::None);
+    test_byte_lit!(r"b'\''", b'\'', EscapeKind::Ascii);
+    test_byte_lit!(r"b'\\'", b'\\', EscapeKind::Ascii);
+    test_byte_lit!(r"b'\x41'", b'A', EscapeKind::Hex);
+    test_byte_lit!(r"b'\xFF'", 0xFF, EscapeKind::Hex);
+    test_byte_lit!(r"b'\n'", b'\n', EscapeKind::Named);
+    test_byte_lit!(r"b'\0'", 0, EscapeKind::Named);
+    assert_eq!(DummyLiteral(r"b'\u{41}'").parse_byte_lit(), None);
+    assert_eq!(DummyLiteral("'a'").parse_byte_lit(), None);
+}
+
+#[test]
+fn byte_errors() {
+    assert_eq!(DummyLiteral(r"b'\u{41}'").parse_byte(), None);
+    assert_eq!(DummyLiteral(r"b'\u{41}'").kind(), LiteralKind::Byte);
+    // Every form of unicode escape is rejected, even malformed ones.
+    for s in &[r"b'\u'", r"b'\u{'", r"b'\u{}'", r"b'\u{FF}'", r"b'\u0041'"] {
+        assert_eq!(DummyLiteral(*s).parse_byte(), None, "{}", s);
+        assert!(DummyLiteral(*s).value().is_none(), "{}", s);
+    }
 }
 
 #[test]
@@ -141,7 +1134,9 @@ fn string() {
     macro_rules! test_string {
         ($i:tt) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::Str);
             assert_eq!(dl.parse_string().unwrap(), $i);
+            assert_eq!(dl.string_len(), Some($i.chars().count()));
             assert_eq!(dl.parse_int(), None);
             assert_eq!(dl.parse_float(), None);
             assert_eq!(dl.parse_char(), None);
@@ -161,6 +1156,7 @@ fn string() {
     test_string!("'");
     test_string!("");
     test_string!("\u{1F415}");
+    test_string!("\x7F");
     test_string!("This
            String contains\
            newlines and other such
@@ -177,6 +1173,156 @@ RAW STRING"#);
 Is
 A r####"Raw string with another in it"####
 RAW STRING"######);
+    test_string!(r###"Contains "## and "# sequences"###);
+    test_string!(r#"Ends with a quote""#);
+}
+
+#[test]
+fn string_lits() {
+    macro_rules! test_string_lit {
+        ($i:tt, $raw:expr, $hashes:expr) => {
+            let dl = DummyLiteral(stringify!($i));
+            let lit = dl.parse_string_lit().unwrap();
+            assert_eq!(lit.value(), $i);
+            assert_eq!(lit.is_raw(), $raw);
+            assert_eq!(lit.hash_count(), $hashes);
+            assert_eq!(lit.into_value(), $i);
+        }
+    }
+
+    test_string_lit!("a\n", false, 0);
+    test_string_lit!(r"a\n", true, 0);
+    test_string_lit!(r#"a"b"#, true, 1);
+    test_string_lit!(r###"a"##b"###, true, 3);
+    assert_eq!(DummyLiteral("b\"a\"").parse_string_lit(), None);
+
+    // Suffixes are recorded, though the value of a suffixed string is not
+    // returned by `parse_string`.
+    let lit = DummyLiteral(r#""foo"bar"#).parse_string_lit().unwrap();
+    assert_eq!((lit.value(), lit.suffix(), lit.is_raw()), ("foo", "bar", false));
+    assert_eq!(DummyLiteral(r#""foo"bar"#).parse_string(), None);
+    let lit = DummyLiteral(r##"r#"a"b"#_x1"##).parse_string_lit().unwrap();
+    assert_eq!((lit.value(), lit.suffix(), lit.hash_count()), (r#"a"b"#, "_x1", 1));
+    let lit = DummyLiteral(r#""a\n"é"#).parse_string_lit().unwrap();
+    assert_eq!((lit.value(), lit.suffix()), ("a\n", "é"));
+    assert_eq!(DummyLiteral(r#""a""#).parse_string_lit().unwrap().suffix(), "");
+    for s in &[r#""a"1"#, r#""a"b c"#, r#""a""b""#, r#""a"-"#, r#""a\q"b"#, r#""a"#] {
+        assert_eq!(DummyLiteral(*s).parse_string_lit(), None, "{}", s);
+    }
+
+    // The literal can be used in place of its value.
+    let lit = DummyLiteral(r##"r#"abc"#"##).parse_string_lit().unwrap();
+    assert_eq!(lit, "abc");
+    assert_eq!(lit, *"abc");
+    assert_eq!(lit, "abc".to_string());
+    assert!(lit != "abd");
+    assert_eq!(lit.len(), 3);
+    assert!(lit.starts_with("ab"));
+    assert_eq!(AsRef::<str>::as_ref(&lit), "abc");
+}
+
+#[test]
+fn raw_hash_counts() {
+    assert_eq!(parse::raw_hash_count(r#"r"a""#), Some(0));
+    assert_eq!(parse::raw_hash_count(r##"r#"a"b"#"##), Some(1));
+    assert_eq!(parse::raw_hash_count(r###"br##"a"#b"##"###), Some(2));
+    assert_eq!(parse::raw_hash_count(r####"cr###""###"####), Some(3));
+    assert_eq!(parse::raw_hash_count(r#""a""#), None);
+    assert_eq!(parse::raw_hash_count(r#"b"a""#), None);
+    assert_eq!(parse::raw_hash_count("r#foo"), None);
+    assert_eq!(parse::raw_hash_count(r##"r#"a""##), None);
+    assert_eq!(parse::raw_hash_count(r##"r#"a"#x"##), None);
+    assert_eq!(parse::raw_hash_count("r\"a\rb\""), None);
+
+    // The count agrees with the one recorded by `parse_string_lit`.
+    let s = r###"r##"a"#"##"###;
+    let lit = DummyLiteral(s).parse_string_lit().unwrap();
+    assert_eq!(parse::raw_hash_count(s), Some(lit.hash_count()));
+}
+
+#[test]
+fn empty_raw_strings() {
+    for s in &["r\"\"", "r#\"\"#", "r###\"\"###"] {
+        let dl = DummyLiteral(*s);
+        assert_eq!(dl.parse_string(), Some(String::new()), "{}", s);
+        assert_eq!(dl.string_len(), Some(0), "{}", s);
+        assert_eq!(parse::string_cow(s), Some("".into()), "{}", s);
+        assert_eq!(DummyLiteral(format!("b{}", s)).parse_bytes(), Some(Vec::new()), "{}", s);
+    }
+    let lit = DummyLiteral("r###\"\"###").parse_string_lit().unwrap();
+    assert_eq!((lit.value(), lit.hash_count()), ("", 3));
+
+    // Only whitespace remains of a string made of line continuations.
+    assert_eq!(DummyLiteral("\"\\\n  \\\n\"").parse_string(), Some(String::new()));
+    assert_eq!(DummyLiteral("r###\"\"##").parse_string(), None);
+    assert_eq!(DummyLiteral("r#\"").parse_string(), None);
+}
+
+#[test]
+fn string_bom() {
+    let dl = DummyLiteral("\"\u{FEFF}abc\"");
+    assert_eq!(dl.parse_string(), Some("\u{FEFF}abc".to_string()));
+    assert_eq!(dl.parse_string_trimmed_bom(), Some("abc".to_string()));
+    let dl = DummyLiteral(r#""\u{FEFF}\u{FEFF}abc""#);
+    assert_eq!(dl.parse_string_trimmed_bom(), Some("\u{FEFF}abc".to_string()));
+    let dl = DummyLiteral("\"a\u{FEFF}\"");
+    assert_eq!(dl.parse_string_trimmed_bom(), Some("a\u{FEFF}".to_string()));
+    assert_eq!(DummyLiteral("r\"\u{FEFF}\"").parse_string_trimmed_bom(), Some(String::new()));
+    assert_eq!(DummyLiteral("'a'").parse_string_trimmed_bom(), None);
+}
+
+#[test]
+fn string_errors() {
+    macro_rules! test_string_err {
+        ($i:expr, $e:expr) => {
+            let dl = DummyLiteral($i);
+            assert_eq!(dl.try_parse_string(), Err($e));
+            assert_eq!(dl.parse_string(), None);
+            assert_eq!(dl.string_len(), None);
+        }
+    }
+
+    test_string_err!(r#""\q""#, LiteralError::InvalidEscape { offset: 1 });
+    test_string_err!(r#""ab\x9""#, LiteralError::InvalidEscape { offset: 3 });
+    test_string_err!(r#""\x80""#, LiteralError::InvalidEscape { offset: 1 });
+    test_string_err!(r#""\xFF""#, LiteralError::InvalidEscape { offset: 1 });
+    test_string_err!(r#""\u{zz}""#, LiteralError::InvalidEscape { offset: 1 });
+    test_string_err!(r#""\u{D800}""#, LiteralError::InvalidCodePoint { offset: 1 });
+    test_string_err!(r#""a\u{110000}""#, LiteralError::InvalidCodePoint { offset: 2 });
+    test_string_err!("\"a\rb\"", LiteralError::BareCarriageReturn { offset: 2 });
+    test_string_err!(r#""abc"#, LiteralError::UnterminatedLiteral { offset: 4 });
+    test_string_err!(r#""abc\"#, LiteralError::UnterminatedLiteral { offset: 5 });
+    test_string_err!(r#""abc"def"#, LiteralError::InvalidSuffix { offset: 5 });
+    test_string_err!(r##"r#"abc"##, LiteralError::UnterminatedLiteral { offset: 6 });
+    test_string_err!(r###"r##"abc"#"###, LiteralError::UnterminatedLiteral { offset: 9 });
+    test_string_err!(r###"r#"abc"##"###, LiteralError::InvalidSuffix { offset: 8 });
+    test_string_err!("r#abc", LiteralError::NotThisKind);
+    test_string_err!("5", LiteralError::NotThisKind);
+    test_string_err!("'a'", LiteralError::NotThisKind);
+}
+
+#[test]
+fn null_escapes() {
+    // NOTE: Rust has no octal escapes, so `\0` is always a single NUL, and any
+    // digits which follow it are literal characters.
+    assert_eq!(DummyLiteral(r#""\0""#).parse_string(), Some("\0".to_string()));
+    assert_eq!(DummyLiteral(r#""\08""#).parse_string(), Some("\08".to_string()));
+    assert_eq!(DummyLiteral(r#""\012""#).parse_string(), Some("\0".to_string() + "12"));
+    assert_eq!(DummyLiteral(r#"b"\012""#).parse_bytes(), Some(vec![0, b'1', b'2']));
+    assert_eq!(DummyLiteral(r"'\0'").parse_char(), Some('\0'));
+    assert_eq!(DummyLiteral(r"b'\0'").parse_byte(), Some(0));
+
+    // No other digit may be escaped.
+    assert_eq!(DummyLiteral(r#""\9""#).try_parse_string(),
+               Err(LiteralError::InvalidEscape { offset: 1 }));
+    assert_eq!(DummyLiteral(r#""a\1""#).try_parse_string(),
+               Err(LiteralError::InvalidEscape { offset: 2 }));
+    assert_eq!(DummyLiteral(r"'\8'").try_parse_char(),
+               Err(LiteralError::InvalidEscape { offset: 1 }));
+    assert_eq!(DummyLiteral(r#"b"\9""#).parse_bytes(), None);
+    assert_eq!(DummyLiteral(r"b'\9'").parse_byte(), None);
+    assert_eq!(DummyLiteral(r"'\01'").try_parse_char(),
+               Err(LiteralError::MultipleCharacters { offset: 3 }));
 }
 
 #[test]
@@ -184,8 +1330,10 @@ fn bytes() {
     macro_rules! test_bytes {
         ($i:tt) => {
             let dl = DummyLiteral(stringify!($i));
+            assert_eq!(dl.kind(), LiteralKind::ByteStr);
             // NOTE: We slice $i here to get it from &[u8; N] to &[u8]
             assert_eq!(dl.parse_bytes().unwrap(), &$i[..]);
+            assert_eq!(dl.bytes_iter().unwrap().collect::<Vec<_>>(), &$i[..]);
             assert_eq!(dl.parse_int(), None);
             assert_eq!(dl.parse_float(), None);
             assert_eq!(dl.parse_string(), None);
@@ -202,6 +1350,7 @@ fn bytes() {
     test_bytes!(b"\t");
     test_bytes!(b"\"");
     test_bytes!(b"'");
+    test_bytes!(b"\x7F\x80\xFF");
     test_bytes!(b"This
            String contains\
            newlines and other such
@@ -218,4 +1367,705 @@ RAW STRING"#);
 Is
 A r####"Raw string with another in it"####
 RAW STRING"######);
+    test_bytes!(br###"Contains "## and "# sequences"###);
+}
+
+#[test]
+fn dummy_from_source() {
+    let dl = DummyLiteral::from_source(r#"b"\xFF\n""#);
+    assert_eq!(dl.to_string(), r#"b"\xFF\n""#);
+    assert_eq!(dl.parse_bytes(), Some(vec![0xFF, b'\n']));
+
+    let dl = DummyLiteral::from_bytes(b"b\"\xC3\xA9\"").unwrap();
+    assert_eq!(dl.parse_bytes(), Some(vec![0xC3, 0xA9]));
+    assert!(DummyLiteral::from_bytes(b"b\"\xFF\"").is_none());
+}
+
+#[test]
+fn utf8_bytes() {
+    assert_eq!(DummyLiteral(r#""a\u{e9}\n""#).parse_utf8_bytes(), Some(b"a\xC3\xA9\n".to_vec()));
+    assert_eq!(DummyLiteral(r##"r#"a\n"#"##).parse_utf8_bytes(), Some(b"a\\n".to_vec()));
+    assert_eq!(DummyLiteral(r#""""#).parse_utf8_bytes(), Some(Vec::new()));
+    // The values of strings and byte strings are alike.
+    assert_eq!(DummyLiteral(r#""a\x41""#).parse_utf8_bytes(),
+               DummyLiteral(r#"b"a\x41""#).parse_bytes());
+    assert_eq!(DummyLiteral(r#"b"a""#).parse_utf8_bytes(), None);
+    assert_eq!(DummyLiteral("'a'").parse_utf8_bytes(), None);
+}
+
+#[test]
+#[cfg(feature = "intern")]
+fn interned_strings() {
+    use std::sync::Arc;
+
+    let a = DummyLiteral(r#""abc""#).parse_interned_string().unwrap();
+    let b = DummyLiteral(r##"r#"abc"#"##).parse_interned_string().unwrap();
+    let c = parse::interned_string(r#""\x61bc""#).unwrap();
+    assert_eq!(&*a, "abc");
+    assert!(Arc::ptr_eq(&a, &b) && Arc::ptr_eq(&a, &c));
+
+    let d = DummyLiteral(r#""abd""#).parse_interned_string().unwrap();
+    assert!(!Arc::ptr_eq(&a, &d));
+    assert_eq!(DummyLiteral("'a'").parse_interned_string(), None);
+    assert_eq!(parse::interned_string(r#""a"#), None);
+
+    // Values outlive the interner, but are not shared after it is cleared.
+    ::clear_interned_strings();
+    let e = DummyLiteral(r#""abc""#).parse_interned_string().unwrap();
+    assert_eq!(a, e);
+    assert!(!Arc::ptr_eq(&a, &e));
+
+    // Each thread has its own interner.
+    let f = std::thread::spawn(|| parse::interned_string(r#""abc""#).unwrap()).join().unwrap();
+    assert!(!Arc::ptr_eq(&e, &f));
+}
+
+#[test]
+fn chars_iter() {
+    for s in &[r#""a\n\u{1F600}\x41é""#, r#""""#, "\"a\\\n   b\"", "\"a\r\nb\"",
+               r##"r#"a\n"b"#"##, "r\"a\r\nb\"", r#"r"""#] {
+        let dl = DummyLiteral(*s);
+        let chars: String = dl.chars_iter().unwrap().collect();
+        assert_eq!(Some(chars), dl.parse_string(), "{}", s);
+        let chars: String = parse::chars_iter(s).unwrap().collect();
+        assert_eq!(Some(chars), parse::string(s), "{}", s);
+    }
+
+    // Searching stops at the match, while the rest is still validated.
+    let mut iter = DummyLiteral(r#""ab\u{7FFF}c""#).chars_iter().unwrap();
+    assert_eq!(iter.position(|c| c == 'b'), Some(1));
+    assert_eq!(iter.collect::<String>(), "\u{7FFF}c");
+
+    for s in &[r#""a\qb""#, r#""a"#, "\"a\rb\"", r#""a"b"#, r#"b"a""#, "'a'", "r#foo", ""] {
+        assert!(DummyLiteral(*s).chars_iter().is_none(), "{}", s);
+    }
+}
+
+#[test]
+fn bytes_errors() {
+    assert_eq!(DummyLiteral(r#"b"\u{41}""#).parse_bytes(), None);
+    assert_eq!(DummyLiteral(r#"b"a\u{41}b""#).parse_bytes(), None);
+    assert_eq!(DummyLiteral(r#"b"\u{41}""#).kind(), LiteralKind::ByteStr);
+    assert!(DummyLiteral(r#"b"a\u{41}b""#).bytes_iter().is_none());
+    assert!(DummyLiteral(r#"b"a"#).bytes_iter().is_none());
+    assert!(DummyLiteral(r#""a""#).bytes_iter().is_none());
+    for s in &[r#"b"\u""#, r#"b"\u{""#, r#"b"\u{}""#, r#"b"\u{FF}""#, r#"b"\u{41"#] {
+        assert_eq!(DummyLiteral(*s).parse_bytes(), None, "{}", s);
+        assert!(DummyLiteral(*s).bytes_iter().is_none(), "{}", s);
+        assert!(DummyLiteral(*s).value().is_none(), "{}", s);
+    }
+}
+
+#[test]
+fn cstrs() {
+    macro_rules! test_cstr {
+        ($i:expr, $e:expr) => {
+            let dl = DummyLiteral($i);
+            assert_eq!(dl.kind(), LiteralKind::CStr);
+            assert_eq!(dl.parse_cstr(), $e.map(|b: &[u8]| b.to_vec()));
+            assert_eq!(dl.parse_string(), None);
+            assert_eq!(dl.parse_bytes(), None);
+        }
+    }
+
+    test_cstr!(r#"c"a""#, Some(&b"a\0"[..]));
+    test_cstr!(r#"c"""#, Some(&b"\0"[..]));
+    test_cstr!(r#"c"\xFF\n""#, Some(&b"\xFF\n\0"[..]));
+    test_cstr!(r#"c"\u{e9}é""#, Some(&b"\xC3\xA9\xC3\xA9\0"[..]));
+    test_cstr!("c\"a\\\n    b\"", Some(&b"ab\0"[..]));
+    test_cstr!(r##"cr#"a"b\0"#"##, Some(&b"a\"b\\0\0"[..]));
+    test_cstr!(r#"cr"""#, Some(&b"\0"[..]));
+    test_cstr!(r#"c"a\0b""#, None);
+    test_cstr!(r#"c"a\x00""#, None);
+    test_cstr!(r#"c"\u{0}""#, None);
+    test_cstr!(r#"c"\q""#, None);
+    test_cstr!(r#"c"a"#, None);
+    test_cstr!(r#"c"a"x"#, None);
+    test_cstr!("c\"\r\"", None);
+}
+
+#[test]
+fn bools() {
+    assert_eq!(DummyLiteral(true).parse_bool(), Some(true));
+    assert_eq!(DummyLiteral(false).parse_bool(), Some(false));
+    assert_eq!(DummyLiteral("True").parse_bool(), None);
+    assert_eq!(DummyLiteral("\"true\"").parse_bool(), None);
+    assert_eq!(DummyLiteral("1").parse_bool(), None);
+}
+
+#[test]
+fn doc_text() {
+    macro_rules! test_doc {
+        ($i:expr, $e:expr) => {
+            let dl = DummyLiteral($i);
+            assert_eq!(dl.doc_text(), $e.map(|s: &str| s.to_string()));
+        }
+    }
+
+    test_doc!("/// hello", Some(" hello"));
+    test_doc!("//! hello", Some(" hello"));
+    test_doc!("///", Some(""));
+    test_doc!("/** hello */", Some(" hello "));
+    test_doc!("/*! hello */", Some(" hello "));
+    test_doc!("/**\n * hello\n * world\n */", Some(" hello\n world"));
+    test_doc!("/**\n   hello\n   world\n*/", Some("   hello\n   world"));
+    test_doc!("//// hello", None);
+    test_doc!("/*** hello */", None);
+    test_doc!("/**/", None);
+    test_doc!("/** hello", None);
+    test_doc!("\"hello\"", None);
+}
+
+#[test]
+fn doc_from_attr() {
+    assert_eq!(DummyLiteral(r#"" hello""#).doc_from_attr(), Some(" hello".to_string()));
+    assert_eq!(DummyLiteral(r#"r" hello""#).doc_from_attr(), Some(" hello".to_string()));
+    assert_eq!(DummyLiteral("/// hello").doc_from_attr(), Some(" hello".to_string()));
+    assert_eq!(DummyLiteral(r#"" hello""#).parse_outer_doc(), None);
+    assert_eq!(DummyLiteral("5").doc_from_attr(), None);
+    assert_eq!(DummyLiteral(r#""\q""#).doc_from_attr(), None);
+}
+
+#[test]
+fn kinds() {
+    assert_eq!(DummyLiteral("/// doc").kind(), LiteralKind::OuterDoc);
+    assert_eq!(DummyLiteral("/** doc */").kind(), LiteralKind::OuterDoc);
+    assert_eq!(DummyLiteral("//! doc").kind(), LiteralKind::InnerDoc);
+    assert_eq!(DummyLiteral("/*! doc */").kind(), LiteralKind::InnerDoc);
+    assert_eq!(DummyLiteral("5f32").kind(), LiteralKind::Float);
+    assert_eq!(DummyLiteral("0x5f32").kind(), LiteralKind::Int);
+    assert_eq!(DummyLiteral("1_000").kind(), LiteralKind::Int);
+    assert_eq!(DummyLiteral("").kind(), LiteralKind::Unknown);
+    assert_eq!(DummyLiteral("foo").kind(), LiteralKind::Unknown);
+    assert_eq!(DummyLiteral("// comment").kind(), LiteralKind::Unknown);
+}
+
+#[test]
+fn empty_token() {
+    // An empty token is not a literal at all, unlike the empty string `""`.
+    let dl = DummyLiteral("");
+    assert_eq!(dl.parse_int(), None);
+    assert_eq!(dl.parse_float(), None);
+    assert_eq!(dl.parse_float_forgiving(), None);
+    assert_eq!(dl.parse_number_f64(), None);
+    assert!(!dl.is_suffixed());
+    assert_eq!(dl.parse_string(), None);
+    assert_eq!(dl.try_parse_string(), Err(LiteralError::NotThisKind));
+    assert_eq!(dl.parse_string_lit(), None);
+    assert_eq!(dl.parse_string_trimmed_bom(), None);
+    assert_eq!(dl.string_len(), None);
+    assert!(dl.chars_iter().is_none());
+    assert_eq!(dl.parse_utf8_bytes(), None);
+    assert_eq!(dl.parse_char(), None);
+    assert_eq!(dl.try_parse_char(), Err(LiteralError::NotThisKind));
+    assert_eq!(dl.parse_char_lit(), None);
+    assert!(!dl.is_lifetime());
+    assert_eq!(dl.parse_bytes(), None);
+    assert!(dl.bytes_iter().is_none());
+    assert_eq!(dl.parse_cstr(), None);
+    assert_eq!(dl.parse_byte(), None);
+    assert_eq!(dl.parse_byte_lit(), None);
+    assert_eq!(dl.parse_bool(), None);
+    assert_eq!(dl.parse_inner_doc(), None);
+    assert_eq!(dl.parse_outer_doc(), None);
+    assert_eq!(dl.doc_text(), None);
+    assert_eq!(dl.doc_from_attr(), None);
+    assert_eq!(dl.kind(), LiteralKind::Unknown);
+    assert_eq!(dl.value(), None);
+    assert_eq!(dl.raw_text(), "");
+    assert!(dl.parse_full().is_none());
+    assert_eq!(dl.parse_lenient().0, None);
+    assert_eq!(dl.expect_string(), Err(LiteralKind::Unknown));
+    assert_eq!(dl.expect_byte(), Err(LiteralKind::Unknown));
+
+    assert_eq!(parse::signed_int(""), None);
+    assert_eq!(parse::int_radix("", 16), None);
+    assert_eq!(parse::string_cow(""), None);
+    assert_eq!(parse::raw_hash_count(""), None);
+    assert_eq!(parse::concat_strings(&[""]), None);
+    assert_eq!(parse::float_special(""), None);
+    assert!(!parse::is_string("") && !parse::is_raw(""));
+
+    let dl = DummyLiteral(r#""""#);
+    assert_eq!(dl.parse_string(), Some(String::new()));
+    assert_eq!(dl.string_len(), Some(0));
+    assert_eq!(dl.chars_iter().map(|i| i.count()), Some(0));
+    assert_eq!(dl.kind(), LiteralKind::Str);
+    assert_eq!(dl.value(), Some(LitValue::Str(String::new())));
+    assert_eq!(DummyLiteral(r#"b"""#).parse_bytes(), Some(Vec::new()));
+}
+
+#[test]
+fn error_offsets() {
+    use core::convert::TryFrom;
+
+    // Each offset points at the start of the problem in the literal's text.
+    let cases: &[(&str, LiteralError)] = &[
+        (r#""ab\qcd""#, LiteralError::InvalidEscape { offset: 3 }),
+        (r#""ab\u{D800}""#, LiteralError::InvalidCodePoint { offset: 3 }),
+        ("\"ab\rcd\"", LiteralError::BareCarriageReturn { offset: 3 }),
+        (r#""ab"#, LiteralError::UnterminatedLiteral { offset: 3 }),
+        (r#""ab"cd"#, LiteralError::InvalidSuffix { offset: 4 }),
+        (r##"r#"ab"#cd"##, LiteralError::InvalidSuffix { offset: 7 }),
+        (r#"b"ab\qcd""#, LiteralError::InvalidEscape { offset: 4 }),
+        (r#"b"ab\u{41}""#, LiteralError::InvalidEscape { offset: 4 }),
+        (r#"b"ab\x4""#, LiteralError::InvalidEscape { offset: 4 }),
+        ("b\"ab\rcd\"", LiteralError::BareCarriageReturn { offset: 4 }),
+        (r#"b"ab"#, LiteralError::UnterminatedLiteral { offset: 4 }),
+        (r#"b"ab\"#, LiteralError::UnterminatedLiteral { offset: 5 }),
+        (r#"b"ab"cd"#, LiteralError::InvalidSuffix { offset: 5 }),
+        (r#"'\q'"#, LiteralError::InvalidEscape { offset: 1 }),
+        ("'ab'", LiteralError::MultipleCharacters { offset: 2 }),
+        ("0b102", LiteralError::InvalidDigit { offset: 4 }),
+        ("0x", LiteralError::MissingDigits { offset: 2 }),
+        ("1.0e", LiteralError::MissingDigits { offset: 4 }),
+        ("0x1_00u8", LiteralError::Overflow { offset: 6 }),
+    ];
+    for &(s, err) in cases {
+        assert_eq!(LitValue::try_from(s), Err(err), "{}", s);
+    }
+
+    assert_eq!(DummyLiteral(r#"b"ab\qcd""#).try_parse_bytes(),
+               Err(LiteralError::InvalidEscape { offset: 4 }));
+    assert_eq!(parse::try_bytes(r#"b"a\n""#), Ok(b"a\n".to_vec()));
+    assert_eq!(parse::try_bytes(r#""a""#), Err(LiteralError::NotThisKind));
+    assert_eq!(parse::try_bytes("br\"a\rb\""), Err(LiteralError::BareCarriageReturn { offset: 4 }));
+}
+
+#[test]
+fn literal_error_display() {
+    use core::convert::TryFrom;
+
+    let err = DummyLiteral(r#""ab\qcd""#).try_parse_string().unwrap_err();
+    assert_eq!(err.offset(), Some(3));
+    assert_eq!(err.to_string(), "invalid escape at offset 3");
+    assert_eq!(LiteralError::NotThisKind.to_string(), "not a literal of the requested kind");
+    assert_eq!(LiteralError::NotThisKind.offset(), None);
+    assert_eq!(LiteralError::UnterminatedLiteral { offset: 2 }.to_string(),
+               "unterminated literal at offset 2");
+    assert_eq!(LiteralError::BareCarriageReturn { offset: 1 }.to_string(),
+               "bare CR not allowed at offset 1");
+    assert_eq!(LiteralError::InvalidCodePoint { offset: 1 }.offset(), Some(1));
+    let err = LitValue::try_from("256u8").unwrap_err();
+    assert_eq!(err, LiteralError::Overflow { offset: 3 });
+    assert_eq!(err.to_string(), "integer out of range for its suffix at offset 3");
+
+    // Errors can be boxed and propagated with `?`.
+    #[cfg(feature = "std")]
+    {
+        fn parse(s: &str) -> Result<char, Box<dyn std::error::Error>> {
+            Ok(DummyLiteral(s).try_parse_char()?)
+        }
+        assert_eq!(parse("'a'").ok(), Some('a'));
+        assert_eq!(parse("'ab'").unwrap_err().to_string(),
+                   "literal contains more than one character at offset 2");
+    }
+}
+
+#[test]
+fn values() {
+    macro_rules! test_value {
+        ($i:expr, $v:expr) => {
+            assert_eq!(DummyLiteral($i).value(), $v);
+        }
+    }
+
+    test_value!("5", Some(LitValue::Int(DummyLiteral("5").parse_int().unwrap())));
+    test_value!("5.0", Some(LitValue::Float(DummyLiteral("5.0").parse_float().unwrap())));
+    test_value!("5f32", Some(LitValue::Float(DummyLiteral("5f32").parse_float().unwrap())));
+    test_value!(r#""a\n""#, Some(LitValue::Str("a\n".to_string())));
+    test_value!(r#"b"a\n""#, Some(LitValue::ByteStr(b"a\n".to_vec())));
+    test_value!(r#"c"a\n""#, Some(LitValue::CStr(b"a\n\0".to_vec())));
+    test_value!("'a'", Some(LitValue::Char('a')));
+    test_value!("b'a'", Some(LitValue::Byte(b'a')));
+    test_value!("//! a", Some(LitValue::InnerDoc("//! a".to_string())));
+    test_value!("/// a", Some(LitValue::OuterDoc("/// a".to_string())));
+    test_value!("foo", None);
+}
+
+#[test]
+fn parse_full() {
+    let dl = DummyLiteral("0x_FFu8");
+    assert_eq!(dl.raw_text(), "0x_FFu8");
+    let full = dl.parse_full().unwrap();
+    assert_eq!(full.text(), "0x_FFu8");
+    assert_eq!(full.value(), &LitValue::Int(dl.parse_int().unwrap()));
+
+    let full = DummyLiteral(r#""a\n""#).parse_full().unwrap();
+    assert_eq!(full.text(), r#""a\n""#);
+    assert_eq!(full.into_value(), LitValue::Str("a\n".to_string()));
+
+    assert_eq!(DummyLiteral("foo").raw_text(), "foo");
+    assert_eq!(DummyLiteral("foo").parse_full(), None);
+}
+
+#[test]
+fn parse_lenient() {
+    macro_rules! test_lenient {
+        ($lit:expr, $value:expr, [$(($range:expr, $message:expr)),*]) => {
+            let (value, diags) = DummyLiteral($lit).parse_lenient();
+            assert_eq!(value, $value);
+            let diags: Vec<_> = diags.iter().map(|d| (d.range(), d.message())).collect();
+            let expected: Vec<(::std::ops::Range<usize>, &str)> = vec![$(($range, $message)),*];
+            assert_eq!(diags, expected);
+        }
+    }
+
+    test_lenient!(r#""a\nb""#, Some(LitValue::Str("a\nb".to_string())), []);
+    test_lenient!("5u8", Some(LitValue::Int(DummyLiteral("5u8").parse_int().unwrap())), []);
+    test_lenient!("256u8", Some(LitValue::Int(DummyLiteral("256u8").parse_int().unwrap())),
+                  [(3..5, "integer out of range for its suffix")]);
+    test_lenient!(r#""a\qb""#, Some(LitValue::Str("a\u{FFFD}b".to_string())),
+                  [(2..4, "unknown escape `\\q`")]);
+    test_lenient!(r#""\q\u{D800}\x80""#, Some(LitValue::Str("\u{FFFD}".repeat(3))),
+                  [(1..3, "unknown escape `\\q`"),
+                   (3..11, "invalid unicode code point `\\u{D800}`"),
+                   (11..15, "invalid hex escape `\\x80`")]);
+    test_lenient!("\"a\rb\"", Some(LitValue::Str("ab".to_string())),
+                  [(2..3, "bare CR not allowed")]);
+    test_lenient!("\"ab", Some(LitValue::Str("ab".to_string())), [(3..3, "unterminated literal")]);
+    test_lenient!("\"a\"b", Some(LitValue::Str("a".to_string())),
+                  [(3..4, "unexpected characters after the literal")]);
+    test_lenient!(r#"b"a\u{41}\xFF""#, Some(LitValue::ByteStr(vec![b'a', 0xFF])),
+                  [(3..9, "unicode escape in a byte literal `\\u{41}`")]);
+    test_lenient!("b\"\u{e9}\"", Some(LitValue::ByteStr(vec![0xC3, 0xA9])), []);
+    test_lenient!("'ab'", Some(LitValue::Char('a')),
+                  [(0..4, "literal contains more than one character")]);
+    test_lenient!("''", None, [(0..2, "empty literal")]);
+    test_lenient!(r"b'\q'", None, [(2..4, "unknown escape `\\q`")]);
+    test_lenient!("0x", None, [(2..2, "missing digits")]);
+    test_lenient!("foo", None, [(0..3, "not a literal")]);
+}
+
+#[test]
+fn expect_kinds() {
+    assert_eq!(DummyLiteral(r#""a""#).expect_string(), Ok("a".to_string()));
+    assert_eq!(DummyLiteral("5").expect_string(), Err(LiteralKind::Int));
+    assert_eq!(DummyLiteral("5u8").expect_int().map(|i| i.as_u8()), Ok(Some(5)));
+    assert_eq!(DummyLiteral("5.0").expect_int(), Err(LiteralKind::Float));
+    assert_eq!(DummyLiteral("5.0").expect_float().map(|f| f.as_f64()), Ok(Some(5.0)));
+    assert_eq!(DummyLiteral("'a'").expect_float(), Err(LiteralKind::Char));
+    assert_eq!(DummyLiteral("'a'").expect_char(), Ok('a'));
+    assert_eq!(DummyLiteral("b'a'").expect_char(), Err(LiteralKind::Byte));
+    assert_eq!(DummyLiteral("b'a'").expect_byte(), Ok(b'a'));
+    assert_eq!(DummyLiteral(r#"b"a""#).expect_byte(), Err(LiteralKind::ByteStr));
+    assert_eq!(DummyLiteral(r#"b"a""#).expect_bytes(), Ok(b"a".to_vec()));
+    assert_eq!(DummyLiteral(r#"c"a""#).expect_bytes(), Err(LiteralKind::CStr));
+    assert_eq!(DummyLiteral("foo").expect_string(), Err(LiteralKind::Unknown));
+
+    // A malformed literal of the expected kind reports that kind.
+    assert_eq!(DummyLiteral(r#""\q""#).expect_string(), Err(LiteralKind::Str));
+}
+
+#[test]
+fn string_prefixes() {
+    macro_rules! test_prefix {
+        ($i:expr, $string:expr, $bytes:expr, $cstr:expr, $raw:expr) => {
+            assert_eq!(parse::is_string($i), $string, "{}", $i);
+            assert_eq!(parse::is_byte_string($i), $bytes, "{}", $i);
+            assert_eq!(parse::is_c_string($i), $cstr, "{}", $i);
+            assert_eq!(parse::is_raw($i), $raw, "{}", $i);
+        }
+    }
+
+    test_prefix!(r#""a""#, true, false, false, false);
+    test_prefix!(r#"r"a""#, true, false, false, true);
+    test_prefix!(r##"r#"a"#"##, true, false, false, true);
+    test_prefix!(r#"b"a""#, false, true, false, false);
+    test_prefix!(r#"br"a""#, false, true, false, true);
+    test_prefix!(r##"br#"a"#"##, false, true, false, true);
+    test_prefix!(r#"c"a""#, false, false, true, false);
+    test_prefix!(r#"cr"a""#, false, false, true, true);
+    test_prefix!(r##"cr#"a"#"##, false, false, true, true);
+    // The body is not inspected.
+    test_prefix!(r#"b"\q"#, false, true, false, false);
+    test_prefix!("r#", false, false, false, false);
+    test_prefix!("r#foo", false, false, false, false);
+    test_prefix!("b'a'", false, false, false, false);
+    test_prefix!("'a'", false, false, false, false);
+    test_prefix!("bar", false, false, false, false);
+    test_prefix!("5", false, false, false, false);
+    test_prefix!("", false, false, false, false);
+}
+
+#[test]
+fn bare_carriage_returns() {
+    // A CR which is not part of a CRLF is an error, wherever it is.
+    let strs = &["\"\r\"", "\"a\rb\"", "\"ab\r\"", "\"\r\r\n\"", "r\"a\rb\"", "r#\"\r\"#"];
+    for s in strs {
+        assert!(parse::try_string(s).is_err(), "{:?}", s);
+        assert_eq!(parse::string_len(s), None, "{:?}", s);
+        assert_eq!(parse::string_cow(s), None, "{:?}", s);
+        let b = format!("b{}", s);
+        assert_eq!(parse::bytes(&b), None, "{:?}", b);
+        assert_eq!(parse::bytes_iter(&b).map(|i| i.collect::<Vec<_>>()), None, "{:?}", b);
+        assert_eq!(parse::cstr(&format!("c{}", s)), None, "{:?}", s);
+    }
+    assert_eq!(parse::try_string("\"ab\r\""),
+               Err(LiteralError::BareCarriageReturn { offset: 3 }));
+    assert_eq!(parse::try_string("r##\"a\r\"##"),
+               Err(LiteralError::BareCarriageReturn { offset: 5 }));
+    assert_eq!(parse::string("\"a\r\nb\""), Some("a\nb".to_string()));
+    assert_eq!(parse::string("r\"a\r\nb\""), Some("a\r\nb".to_string()));
+    assert_eq!(parse::bytes("b\"a\r\nb\""), Some(b"a\nb".to_vec()));
+}
+
+#[test]
+fn line_continuations() {
+    // Only ASCII whitespace is skipped after a line continuation, so a unicode
+    // space is kept, in strings and byte strings alike.
+    let dl = DummyLiteral("\"a\\\n \t\u{3000}b\"");
+    assert_eq!(dl.parse_string(), Some("a\u{3000}b".to_string()));
+    assert_eq!(dl.string_len(), Some(3));
+    let dl = DummyLiteral("b\"a\\\n \t\u{3000}b\"");
+    assert_eq!(dl.parse_bytes(), Some(b"a\xE3\x80\x80b".to_vec()));
+    assert_eq!(dl.bytes_iter().map(|i| i.collect::<Vec<_>>()),
+               Some(b"a\xE3\x80\x80b".to_vec()));
+    let dl = DummyLiteral("c\"a\\\n \t\u{3000}b\"");
+    assert_eq!(dl.parse_cstr(), Some(b"a\xE3\x80\x80b\0".to_vec()));
+
+    // All ASCII whitespace, including further newlines, is skipped.
+    let dl = DummyLiteral("\"a\\\r\n\n\r\n  \tb\"");
+    assert_eq!(dl.parse_string(), Some("ab".to_string()));
+    let dl = DummyLiteral("b\"a\\\r\n\n\r\n  \tb\"");
+    assert_eq!(dl.parse_bytes(), Some(b"ab".to_vec()));
+
+    // A continuation which runs into the end of the input is unterminated.
+    // These are parsed directly, as the whitespace would be trimmed from a
+    // token.
+    for s in &["\"abc\\\n", "\"abc\\\n \t\r\n", "\"abc\\\r", "\"abc\\"] {
+        assert_eq!(parse::try_string(s), Err(LiteralError::UnterminatedLiteral {
+            offset: s.len(),
+        }));
+        assert_eq!(parse::string_len(s), None);
+        assert_eq!(parse::string_cow(s), None);
+        let b = format!("b{}", s);
+        assert_eq!(parse::bytes(&b), None);
+        assert_eq!(parse::bytes_iter(&b).map(|i| i.collect::<Vec<_>>()), None);
+        assert_eq!(parse::cstr(&format!("c{}", s)), None);
+    }
+}
+
+#[test]
+fn lit_value_try_from() {
+    use core::convert::TryFrom;
+
+    assert_eq!(LitValue::try_from(r#"b"hi""#), Ok(LitValue::ByteStr(b"hi".to_vec())));
+    assert_eq!(LitValue::try_from("'a'"), Ok(LitValue::Char('a')));
+    assert_eq!(LitValue::try_from("5u8"), Ok(LitValue::Int("5u8".parse::<IntLit>().unwrap())));
+    assert_eq!(LitValue::try_from("/// a"), Ok(LitValue::OuterDoc("/// a".to_string())));
+
+    assert_eq!(LitValue::try_from(r#""\q""#), Err(LiteralError::InvalidEscape { offset: 1 }));
+    assert_eq!(LitValue::try_from("'ab'"), Err(LiteralError::MultipleCharacters { offset: 2 }));
+    assert_eq!(LitValue::try_from("5px"), Err(LiteralError::InvalidSuffix { offset: 1 }));
+    assert_eq!(LitValue::try_from(r#"b"\q""#), Err(LiteralError::InvalidEscape { offset: 2 }));
+    assert_eq!(LitValue::try_from("b'ab'"), Err(LiteralError::MultipleCharacters { offset: 3 }));
+    assert_eq!(LitValue::try_from(r"b'\u{41}'"), Err(LiteralError::InvalidEscape { offset: 2 }));
+    assert_eq!(LitValue::try_from("b'é'"), Err(LiteralError::MultipleCharacters { offset: 2 }));
+    assert_eq!(LitValue::try_from("b''"), Err(LiteralError::UnterminatedLiteral { offset: 3 }));
+    assert_eq!(LitValue::try_from(r#"c"a\0b""#), Err(LiteralError::InvalidCodePoint { offset: 3 }));
+    assert_eq!(LitValue::try_from(r#"c"\q""#), Err(LiteralError::InvalidEscape { offset: 2 }));
+    assert_eq!(LitValue::try_from("c\"a"), Err(LiteralError::UnterminatedLiteral { offset: 3 }));
+    assert_eq!(LitValue::try_from("foo"), Err(LiteralError::NotThisKind));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    use FloatLit;
+
+    for i in &["5", "0xFFu8", "99999999999999999999999999999999999999999i32"] {
+        let asint = DummyLiteral(i).parse_int().unwrap();
+        let json = serde_json::to_string(&asint).unwrap();
+        assert_eq!(serde_json::from_str::<IntLit>(&json).unwrap(), asint);
+    }
+    for f in &["5.5", "1e10f32", "0.25f64"] {
+        let asfloat = DummyLiteral(f).parse_float().unwrap();
+        let json = serde_json::to_string(&asfloat).unwrap();
+        let de = serde_json::from_str::<FloatLit>(&json).unwrap();
+        assert_eq!(de, asfloat);
+        assert_eq!(de.repr(), asfloat.repr());
+    }
+    let old: FloatLit = serde_json::from_str(r#"{"val":1e10,"suffix":""}"#).unwrap();
+    assert_eq!(old.repr().to_string(), "10000000000.0");
+
+    let json = serde_json::to_string(&DummyLiteral("5u8").parse_int().unwrap()).unwrap();
+    assert!(serde_json::from_str::<IntLit>(&json.replace("u8", "px")).is_err());
+    let json = serde_json::to_string(&DummyLiteral("5f32").parse_float().unwrap()).unwrap();
+    assert!(serde_json::from_str::<FloatLit>(&json.replace("f32", "f16")).is_err());
+    let json = serde_json::to_string(&parse::int_radix("zz", 36).unwrap()).unwrap();
+    assert!(serde_json::from_str::<IntLit>(&json).is_ok());
+    assert!(serde_json::from_str::<IntLit>(&json.replace("36", "16")).is_err());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn syn_lits() {
+    use syn::{self, Lit};
+
+    let lit = |s: &str| syn::parse_str::<Lit>(s).unwrap();
+
+    assert_eq!(lit("0x_FFu8").parse_int().and_then(|i| i.as_u8()), Some(255));
+    assert_eq!(lit("0x_FFu8").parse_int().map(|i| i.int_base()), Some(IntBase::Hexadecimal));
+    assert_eq!(lit("0x_FFu8").parse_int(), DummyLiteral("0x_FFu8").parse_int());
+    let neg = Lit::Int(syn::LitInt::new("-5i32", lit("5").span()));
+    assert_eq!(neg.parse_int().and_then(|i| i.as_i32()), Some(-5));
+    assert_eq!(lit("1_000").parse_int().map(|i| i.raw_digits().to_string()),
+               Some("1000".to_string()));
+    assert_eq!(lit("0x_FFu8").parse_int().and_then(|i| i.suffix_offset()), Some(5));
+    assert_eq!(lit("5px").parse_int(), None);
+    assert_eq!(lit("1e1_0f32").parse_float().and_then(|f| f.as_f32()), Some(1e10));
+    assert_eq!(lit("5f64").parse_float().and_then(|f| f.as_f64()), Some(5.0));
+    assert_eq!(lit("1e1_0f32").parse_float().map(|f| f.repr().to_string()),
+               Some("1e1_0".to_string()));
+    assert_eq!(lit("5f64").parse_int(), None);
+    assert_eq!(lit("5f64").kind(), LiteralKind::Float);
+    assert_eq!(lit("5").parse_float(), None);
+    assert_eq!(lit("0x_FFu8").parse_number_f64(), Some(255.0));
+    assert_eq!(lit("5f32").parse_number_f64(), Some(5.0));
+    assert_eq!(lit("5f32").expect_float().map(|f| f.as_f32()), Ok(Some(5.0)));
+    assert_eq!(lit("5f32").expect_int(), Err(LiteralKind::Float));
+    assert!(lit("5f32").is_suffixed() && lit("0x_FFu8").is_suffixed() && !lit("5.0").is_suffixed());
+    assert_eq!(lit(r#""a\nb""#).parse_string(), Some("a\nb".to_string()));
+    assert_eq!(lit(r##"r#"a"#"##).parse_string_lit().map(|s| s.is_raw()), Some(true));
+    assert_eq!(lit("'a'").parse_char(), Some('a'));
+    assert_eq!(lit("'a'").try_parse_string(), Err(LiteralError::NotThisKind));
+    assert_eq!(lit(r#"b"a\n""#).parse_bytes(), Some(b"a\n".to_vec()));
+    assert_eq!(lit(r#""a\n""#).parse_utf8_bytes(), Some(b"a\n".to_vec()));
+    assert_eq!(lit("b'a'").parse_byte(), Some(b'a'));
+    assert_eq!(lit("true").kind(), LiteralKind::Unknown);
+    assert_eq!(lit("true").value(), None);
+    assert_eq!(lit("true").parse_bool(), Some(true));
+    assert_eq!(lit("5").parse_bool(), None);
+    assert_eq!(lit("b'a'").kind(), LiteralKind::Byte);
+    assert_eq!(lit("'a'").value(), Some(LitValue::Char('a')));
+    assert_eq!(lit("256u8").value(), None);
+    assert_eq!(lit("256u8").parse_lenient().1, DummyLiteral("256u8").parse_lenient().1);
+    assert_eq!(lit("0x_FFu8").raw_text(), "0x_FFu8");
+    assert_eq!(lit("true").raw_text(), "true");
+    assert_eq!(lit("'a'").parse_full().map(|p| p.text().to_string()), Some("'a'".to_string()));
+}
+
+#[test]
+fn from_str() {
+    use FloatLit;
+
+    assert_eq!("42u8".parse::<IntLit>().unwrap().as_u8(), Some(42));
+    assert_eq!("0x_FF".parse::<IntLit>().unwrap().as_u8(), Some(255));
+    assert_eq!("3.5f32".parse::<FloatLit>().unwrap().as_f32(), Some(3.5));
+    assert_eq!("1_0.2_5e1_0".parse::<FloatLit>().unwrap().as_f64(), Some(10.25e10));
+
+    assert_eq!("\"a\"".parse::<IntLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("5.0".parse::<IntLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("5f32".parse::<IntLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("5px".parse::<IntLit>(), Err(LiteralError::InvalidSuffix { offset: 1 }));
+    assert_eq!("0b_102".parse::<IntLit>(), Err(LiteralError::InvalidDigit { offset: 5 }));
+    assert_eq!("0o8".parse::<IntLit>(), Err(LiteralError::InvalidDigit { offset: 2 }));
+    assert_eq!("0x".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 2 }));
+    assert_eq!("0x_".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("0x__".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 4 }));
+    assert_eq!("0b".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 2 }));
+    assert_eq!("0o__".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 4 }));
+    assert_eq!("0x_u8".parse::<IntLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!(DummyLiteral("0x_").parse_int(), None);
+    assert_eq!(parse::int_lenient("0X"), None);
+    assert_eq!(parse::int_lenient("+"), None);
+    assert_eq!(parse::int_lenient("+-5"), None);
+    assert_eq!("0x_0".parse::<IntLit>().unwrap().as_u8(), Some(0));
+    assert_eq!("1__0".parse::<IntLit>().unwrap().as_u8(), Some(10));
+    assert_eq!("1_".parse::<IntLit>().unwrap().as_u8(), Some(1));
+
+    assert_eq!("5".parse::<FloatLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("5u8".parse::<FloatLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("0x5".parse::<FloatLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("'a'".parse::<FloatLit>(), Err(LiteralError::NotThisKind));
+    assert_eq!("5.0px".parse::<FloatLit>(), Err(LiteralError::InvalidSuffix { offset: 3 }));
+    assert_eq!("1_0.5f16".parse::<FloatLit>(), Err(LiteralError::InvalidSuffix { offset: 5 }));
+    assert_eq!("1.2.3".parse::<FloatLit>(), Err(LiteralError::InvalidSuffix { offset: 3 }));
+
+    assert_eq!(DummyLiteral("1.0e").parse_float(), None);
+    assert_eq!(DummyLiteral("1.0e+").parse_float(), None);
+    assert_eq!(DummyLiteral("1.0e10").parse_float().unwrap().as_f64(), Some(1.0e10));
+    assert_eq!("1.0e".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 4 }));
+    assert_eq!("1.0E+".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 5 }));
+    assert_eq!("1e_f32".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("1e+-5".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("1e--5".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("1e-+5".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("1e_+5".parse::<FloatLit>(), Err(LiteralError::MissingDigits { offset: 3 }));
+    assert_eq!("1e5-".parse::<FloatLit>(), Err(LiteralError::InvalidSuffix { offset: 3 }));
+    assert_eq!(DummyLiteral("1e+-5").parse_float(), None);
+    assert_eq!(DummyLiteral("1e+-5").value(), None);
+    assert_eq!(DummyLiteral("1e-_5").parse_float().unwrap().as_f64(), Some(1e-5));
+}
+
+#[test]
+fn no_panics() {
+    use escape::{self, EscapeToken};
+
+    // Every combination of up to 4 of these characters is run through each
+    // parser, which covers the truncated and malformed forms of each kind of
+    // literal. Longer inputs are covered by the fuzz target in `fuzz/`.
+    const PIECES: &[&str] = &[
+        "\"", "'", "\\", "r", "#", "b", "c", "x", "u", "{", "}", "0", "f", "e",
+        ".", "_", "\r", "\n", "\u{e9}", "/", "*", "!", "-",
+    ];
+
+    fn check(s: &str) {
+        let dl = DummyLiteral(s);
+        let _ = dl.value();
+        let _ = dl.kind();
+        let _ = dl.parse_int();
+        let _ = dl.parse_float();
+        let _ = dl.try_parse_string();
+        let _ = dl.string_len();
+        let _ = dl.parse_string_lit();
+        let _ = dl.try_parse_char();
+        let _ = dl.parse_bytes();
+        let _ = dl.bytes_iter().map(|i| i.count());
+        assert_eq!(dl.chars_iter().map(|i| i.collect::<String>()), dl.parse_string(), "{:?}", s);
+        let _ = dl.parse_cstr();
+        let _ = dl.parse_byte();
+        let _ = dl.doc_from_attr();
+        let _ = parse::signed_int(s);
+        let _ = parse::int_lenient(s);
+        let _ = parse::string_cow(s);
+        let _ = escape::next(s);
+
+        // Recovery must report a problem exactly when the strict parser does.
+        let (value, diags) = dl.parse_lenient();
+        match dl.value() {
+            Some(v) => assert!(value == Some(v) && diags.is_empty(), "{:?}", s),
+            None => assert!(!diags.is_empty(), "{:?}", s),
+        }
+
+        // Stepping through a valid string's body must decode the same value.
+        if let (Some(value), Some(mut body)) = (parse::string(s), s.strip_prefix('"')) {
+            let mut out = String::new();
+            while let Some((token, len)) = escape::next(body) {
+                match token {
+                    EscapeToken::Char(ch) => out.push(ch),
+                    EscapeToken::Literal(text) => out.push_str(text),
+                    EscapeToken::Continuation => {}
+                }
+                body = &body[len..];
+            }
+            assert_eq!((&out[..], body), (&value[..], "\""), "{:?}", s);
+        }
+    }
+
+    fn extend(prefix: &mut String, depth: usize) {
+        check(prefix);
+        if depth == 0 {
+            return;
+        }
+        for piece in PIECES {
+            let len = prefix.len();
+            prefix.push_str(piece);
+            extend(prefix, depth - 1);
+            prefix.truncate(len);
+        }
+    }
+
+    extend(&mut String::new(), 4);
 }
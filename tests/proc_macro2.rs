@@ -0,0 +1,192 @@
+//! Checks that the literals which `proc_macro2` constructs are decoded by the
+//! `LiteralExt` implementation for `proc_macro2::Literal`, which reads the
+//! text produced by its `Display` implementation.
+#![cfg(feature = "proc-macro2")]
+
+extern crate literalext;
+extern crate proc_macro2;
+
+use literalext::{IntTokenError, LiteralExt, LiteralExtSpanned, LiteralKind, LitValue};
+use std::convert::TryFrom;
+use proc_macro2::{Literal, Span, Term, TokenNode, TokenStream, TokenTree};
+
+#[test]
+fn ints() {
+    let lit = Literal::u32(5);
+    assert_eq!(lit.kind(), LiteralKind::Int);
+    assert_eq!(lit.parse_int().and_then(|i| i.as_u32()), Some(5));
+    assert_eq!(lit.parse_int().map(|i| i.suffix().to_string()), Some("u32".to_string()));
+
+    assert_eq!(Literal::u8(255).parse_int().and_then(|i| i.as_u8()), Some(255));
+    assert_eq!(Literal::u64(u64::MAX).parse_int().and_then(|i| i.as_u64()), Some(u64::MAX));
+    assert_eq!(Literal::usize(7).parse_int().and_then(|i| i.as_usize()), Some(7));
+    assert_eq!(Literal::i64(i64::MAX).parse_int().and_then(|i| i.as_i64()), Some(i64::MAX));
+    assert_eq!(Literal::integer(42).parse_int().and_then(|i| i.as_u8()), Some(42));
+    assert_eq!(Literal::integer(42).parse_int().map(|i| i.suffix().to_string()),
+               Some(String::new()));
+}
+
+#[test]
+fn floats() {
+    let lit = Literal::f32(1.5);
+    assert_eq!(lit.kind(), LiteralKind::Float);
+    assert_eq!(lit.parse_float().and_then(|f| f.as_f32()), Some(1.5));
+    assert_eq!(lit.parse_float().map(|f| f.suffix().to_string()), Some("f32".to_string()));
+
+    assert_eq!(Literal::f64(0.25).parse_float().and_then(|f| f.as_f64()), Some(0.25));
+    assert_eq!(Literal::f64(1e100).parse_float().and_then(|f| f.as_f64()), Some(1e100));
+    assert_eq!(Literal::float(2.5).parse_float().and_then(|f| f.as_f64()), Some(2.5));
+    assert_eq!(Literal::float(2.0).parse_float().and_then(|f| f.as_f64()), Some(2.0));
+}
+
+#[test]
+fn integral_floats() {
+    // `f64` renders integral values without a `.`, but keeps the suffix.
+    let lit = Literal::f64(1.0);
+    assert_eq!(lit.to_string(), "1f64");
+    assert_eq!(lit.parse_float().and_then(|f| f.as_f64()), Some(1.0));
+    assert_eq!(lit.parse_float_forgiving().and_then(|f| f.as_f64()), Some(1.0));
+
+    // `proc_macro2` 0.2 has no `f64_unsuffixed`, but in versions which do, it
+    // renders `1.0` as `1`, the same as this integer.
+    let lit = Literal::integer(1);
+    assert_eq!(lit.parse_float(), None);
+    assert_eq!(lit.parse_float_forgiving().and_then(|f| f.as_f64()), Some(1.0));
+    assert_eq!(lit.parse_float_forgiving().map(|f| f.suffix().to_string()),
+               Some(String::new()));
+    assert_eq!(Literal::u8(1).parse_float_forgiving(), None);
+    assert_eq!(Literal::float(1.5).parse_float_forgiving().and_then(|f| f.as_f64()), Some(1.5));
+}
+
+#[test]
+fn negative_ints() {
+    // Negative values are rendered with their sign, which `Literal` tokens
+    // parsed from source never contain, so they must be read with
+    // `parse::signed_int`.
+    let lit = Literal::i32(-5);
+    assert_eq!(lit.to_string(), "-5i32");
+    assert_eq!(lit.parse_int(), None);
+    assert_eq!(literalext::parse::signed_int(&lit.to_string()).and_then(|i| i.as_i32()),
+               Some(-5));
+}
+
+#[test]
+fn strings() {
+    for s in &["", "a", "a\nb\t\"c\"\\", "\u{0}\u{7f}é\u{1F600}", "\r\n"] {
+        let lit = Literal::string(s);
+        assert_eq!(lit.kind(), LiteralKind::Str);
+        assert_eq!(lit.parse_string().as_ref().map(|s| &s[..]), Some(*s));
+        assert_eq!(lit.string_len(), Some(s.chars().count()));
+        assert_eq!(lit.value(), Some(LitValue::Str(s.to_string())));
+    }
+
+    let lit = Literal::raw_string("a\\n\"b", 1);
+    assert_eq!(lit.parse_string(), Some("a\\n\"b".to_string()));
+    assert_eq!(lit.parse_string_lit().map(|s| (s.is_raw(), s.hash_count())), Some((true, 1)));
+}
+
+#[test]
+fn chars() {
+    for &c in &['a', '\'', '"', '\\', '\n', '\0', 'é', '\u{1F600}', '\u{7f}'] {
+        let lit = Literal::character(c);
+        assert_eq!(lit.kind(), LiteralKind::Char);
+        assert_eq!(lit.parse_char(), Some(c));
+        assert_eq!(lit.parse_char_lit().map(|c| c.value()), Some(c));
+    }
+}
+
+#[test]
+fn bytes() {
+    let all: Vec<u8> = (0..=255).collect();
+    let lit = Literal::byte_string(&all);
+    assert_eq!(lit.kind(), LiteralKind::ByteStr);
+    assert_eq!(lit.parse_bytes(), Some(all.clone()));
+    assert_eq!(lit.bytes_iter().map(|i| i.collect::<Vec<_>>()), Some(all.clone()));
+
+    for &b in &all {
+        let lit = Literal::byte_char(b);
+        assert_eq!(lit.kind(), LiteralKind::Byte);
+        assert_eq!(lit.parse_byte(), Some(b));
+    }
+
+    let lit = Literal::raw_byte_string("a\\n", 2);
+    assert_eq!(lit.parse_bytes(), Some(b"a\\n".to_vec()));
+}
+
+#[test]
+fn docs() {
+    let lit = Literal::doccomment("/// a");
+    assert_eq!(lit.kind(), LiteralKind::OuterDoc);
+    assert_eq!(lit.parse_outer_doc(), Some("/// a".to_string()));
+    assert_eq!(lit.doc_text(), Some(" a".to_string()));
+
+    let lit = Literal::doccomment("//! a");
+    assert_eq!(lit.kind(), LiteralKind::InnerDoc);
+    assert_eq!(lit.parse_inner_doc(), Some("//! a".to_string()));
+}
+
+#[test]
+fn spans() {
+    let tree = TokenTree { span: Span::call_site(), kind: TokenNode::Literal(Literal::u8(5)) };
+    match tree.parse_with_span() {
+        (Some(LitValue::Int(i)), _) => assert_eq!(i.as_u8(), Some(5)),
+        (value, _) => panic!("expected an integer, got {:?}", value),
+    }
+
+    let tree = TokenTree::from(TokenNode::Literal(Literal::string("a")));
+    assert_eq!(tree.parse_with_span().0, Some(LitValue::Str("a".to_string())));
+
+    let tree = TokenTree::from(TokenNode::Term(Term::intern("a")));
+    assert_eq!(tree.parse_with_span().0, None);
+}
+
+#[test]
+fn stream_literals() {
+    let stream: TokenStream = r#"foo(1, "a", [b'c', { 'd' }]) + 2.5 - 'e"#.parse().unwrap();
+    let values: Vec<_> = literalext::literals(stream).map(|(_, value)| value).collect();
+    assert_eq!(values.len(), 5);
+    assert_eq!(values[0], LitValue::Int(literalext::parse::int("1").unwrap()));
+    assert_eq!(values[1], LitValue::Str("a".to_string()));
+    assert_eq!(values[2], LitValue::Byte(b'c'));
+    assert_eq!(values[3], LitValue::Char('d'));
+    assert_eq!(values[4], LitValue::Float(literalext::parse::float("2.5").unwrap()));
+
+    assert_eq!(literalext::literals(TokenStream::empty()).count(), 0);
+}
+
+#[test]
+fn int_tokens() {
+    let token = |s: &str| {
+        Literal::try_from(literalext::parse::signed_int(s).unwrap()).map(|t| t.to_string())
+    };
+    assert_eq!(token("5u8"), Ok("5u8".to_string()));
+    assert_eq!(token("0xFF_u16"), Ok("255u16".to_string()));
+    assert_eq!(token("42"), Ok("42".to_string()));
+    #[cfg(feature = "i128")]
+    assert_eq!(token("5u128"), Ok("5u128".to_string()));
+    // Values which can't be built with a constructor keep their digits.
+    assert_eq!(token("0x1_00u8"), Ok("0x100u8".to_string()));
+    assert_eq!(token("99999999999999999999999999999999999999999"),
+               Ok("99999999999999999999999999999999999999999".to_string()));
+    // A negative number is not a single token.
+    assert_eq!(token("-5i32"), Err(IntTokenError::Negative));
+
+    let lit = Literal::try_from(literalext::parse::int("0x1_00u8").unwrap()).unwrap();
+    assert_eq!(lit.parse_int().map(|i| i.raw_digits().to_string()), Some("100".to_string()));
+
+    let custom = literalext::parse::int_with_suffixes("5px", &["px"]).unwrap();
+    assert_eq!(Literal::try_from(custom).map(|t| t.to_string()), Err(IntTokenError::CustomSuffix));
+}
+
+#[test]
+fn float_tokens() {
+    let token = |s: &str| Literal::from(literalext::parse::float(s).unwrap()).to_string();
+    assert_eq!(token("1.5f32"), "1.5f32");
+    assert_eq!(token("2.5e1f64"), "25f64");
+    assert_eq!(token("0.25"), "0.25");
+    assert_eq!(token("1e400"), "1e400");
+    assert_eq!(token("1e40f32"), "1e40f32");
+
+    let lit = Literal::from(literalext::parse::float("1.5f32").unwrap());
+    assert_eq!(lit.parse_float().and_then(|f| f.as_f32()), Some(1.5));
+}